@@ -3,7 +3,7 @@ use std::fs::create_dir_all;
 
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
-use cw_zodiatic::msg::{ExecuteMsg, GetLunarResponse, InstantiateMsg, QueryMsg};
+use cw_zodiatic::msg::{ExecuteMsg, GetLunarResponse, InstantiateMsg, QueryMsg, SudoMsg};
 use cw_zodiatic::state::State;
 
 fn main() {
@@ -15,6 +15,7 @@ fn main() {
     export_schema(&schema_for!(InstantiateMsg), &out_dir);
     export_schema(&schema_for!(ExecuteMsg), &out_dir);
     export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(SudoMsg), &out_dir);
     export_schema(&schema_for!(State), &out_dir);
     export_schema(&schema_for!(GetLunarResponse), &out_dir);
 }
@@ -1,54 +1,135 @@
+use std::ops::Range;
+
 use bitvec::{prelude::*, slice::IterOnes};
 
 type BV = BitVec<u32, Lsb0>;
 
+/// A set of row ids over an explicit universe `0..len()`.
+///
+/// Binary operations (`and`, `or`, `xor`, `and_not`) always keep the length of `self`:
+/// bits of the other operand beyond that length are ignored, and bits it does not have
+/// are read as zeros. Start a query from `BitMap::ones(rows)` to get a result over
+/// exactly `rows` rows, whatever the lengths of the stored index bitmaps are.
 #[derive(Clone, Debug, PartialEq)]
 pub struct BitMap {
     bv: BV,
 }
 
 impl BitMap {
+    /// An empty bitmap over a universe of 32 rows, i.e. one storage word.
     pub fn new() -> Self {
         Self::from_vec(vec![0])
     }
 
+    /// Restores a bitmap from its storage words. The universe is `32 * vec.len()`.
     pub fn from_vec(vec: Vec<u32>) -> Self {
         BitMap {
             bv: BV::from_vec(vec),
         }
     }
 
+    pub fn zeros(len: usize) -> Self {
+        BitMap {
+            bv: BV::repeat(false, len),
+        }
+    }
+
     pub fn ones(len: usize) -> Self {
         BitMap {
             bv: BV::repeat(true, len),
         }
     }
 
-    pub fn into_vec(self) -> Vec<u32> {
+    /// Returns the storage words. Bits past `len()` in the last word are always zero,
+    /// so `from_vec(bm.into_vec())` holds the same rows as `bm`.
+    pub fn into_vec(mut self) -> Vec<u32> {
+        self.bv.set_uninitialized(false);
         self.bv.into_vec()
     }
 
+    pub fn len(&self) -> usize {
+        self.bv.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bv.is_empty()
+    }
+
+    /// Grows (with zeros) or truncates the universe to `len` rows.
+    pub fn resize(&mut self, len: usize) -> &Self {
+        self.bv.resize(len, false);
+        self
+    }
+
+    /// Adds `index` to the set, growing the universe to `index + 1` if needed.
     pub fn set(&mut self, index: usize) -> &Self {
+        if index >= self.bv.len() {
+            self.bv.resize(index + 1, false);
+        }
         self.bv.set(index, true);
         self
     }
 
+    /// Removes `index` from the set. Rows outside the universe are already absent.
+    pub fn clear(&mut self, index: usize) -> &Self {
+        if index < self.bv.len() {
+            self.bv.set(index, false);
+        }
+        self
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.bv.get(index).map(|bit| *bit).unwrap_or(false)
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.bv.count_ones()
+    }
+
+    /// Resizes the universe to `len` rows, then complements the set within it.
+    pub fn not(&mut self, len: usize) -> &Self {
+        self.bv.resize(len, false);
+        self.bv = !std::mem::take(&mut self.bv);
+        self
+    }
+
     pub fn and(&mut self, bitmap: BitMap) -> &Self {
         self.bv &= bitmap.bv;
         self
     }
 
     pub fn or(&mut self, bitmap: BitMap) -> &Self {
+        let len = self.bv.len();
         self.bv |= bitmap.bv;
+        self.bv.truncate(len);
         self
     }
 
     pub fn xor(&mut self, bitmap: BitMap) -> &Self {
+        let len = self.bv.len();
         self.bv ^= bitmap.bv;
+        self.bv.truncate(len);
         self
     }
 
-    pub fn iter_ones(&self) -> IterOnes<u32, Lsb0> {
+    /// Removes every row of `bitmap` from the set.
+    pub fn and_not(&mut self, mut bitmap: BitMap) -> &Self {
+        bitmap.not(self.bv.len());
+        self.and(bitmap)
+    }
+
+    /// Returns the rows in `range`, renumbered from zero. The result has `range.len()`
+    /// rows; the part of `range` beyond `len()` is empty.
+    pub fn slice(&self, range: Range<usize>) -> BitMap {
+        let mut bm = BitMap::zeros(range.len());
+        let end = range.end.min(self.bv.len());
+        if range.start < end {
+            bm.bv[..end - range.start].copy_from_bitslice(&self.bv[range.start..end]);
+        }
+        bm
+    }
+
+    pub fn iter_ones(&self) -> IterOnes<'_, u32, Lsb0> {
         self.bv.iter_ones()
     }
 }
@@ -58,3 +139,148 @@ impl Default for BitMap {
         BitMap::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn from_bools(bools: &[bool]) -> BitMap {
+        let mut bm = BitMap::zeros(bools.len());
+        bools
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| **b)
+            .for_each(|(i, _)| {
+                bm.set(i);
+            });
+        bm
+    }
+
+    type BinaryOp = (fn(&mut BitMap, BitMap), fn(bool, bool) -> bool);
+
+    fn to_bools(bm: &BitMap) -> Vec<bool> {
+        (0..bm.len()).map(|i| bm.contains(i)).collect()
+    }
+
+    /// Applies `op` row by row over the universe of `lhs`, reading missing `rhs` bits as zeros
+    fn model(lhs: &[bool], rhs: &[bool], op: fn(bool, bool) -> bool) -> Vec<bool> {
+        lhs.iter()
+            .enumerate()
+            .map(|(i, l)| op(*l, rhs.get(i).copied().unwrap_or(false)))
+            .collect()
+    }
+
+    #[test]
+    fn set_grows_universe() {
+        let mut bm = BitMap::new();
+        assert_eq!(32, bm.len());
+
+        bm.set(365);
+        assert_eq!(366, bm.len());
+        assert!(bm.contains(365));
+        assert!(!bm.contains(366));
+
+        let bm = BitMap::from_vec(bm.into_vec());
+        assert_eq!(vec![365], bm.iter_ones().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn and_keeps_lhs_length() {
+        // a stored index bitmap only covers the first 32 rows
+        let mut index = BitMap::new();
+        index.set(3);
+
+        let mut bm = BitMap::ones(366);
+        bm.and(index.clone());
+        assert_eq!(366, bm.len());
+        assert_eq!(vec![3], bm.iter_ones().collect::<Vec<_>>());
+
+        let mut bm = BitMap::ones(366);
+        bm.and_not(index);
+        assert_eq!(366, bm.len());
+        assert_eq!(365, bm.count_ones());
+        assert!(!bm.contains(3));
+    }
+
+    #[test]
+    fn not_and_slice() {
+        let mut bm = BitMap::zeros(4);
+        bm.set(1);
+        bm.not(6);
+        assert_eq!(vec![0, 2, 3, 4, 5], bm.iter_ones().collect::<Vec<_>>());
+
+        let slice = bm.slice(2..8);
+        assert_eq!(6, slice.len());
+        assert_eq!(vec![0, 1, 2, 3], slice.iter_ones().collect::<Vec<_>>());
+    }
+
+    proptest! {
+        #[test]
+        fn binary_ops_match_model(
+            lhs in prop::collection::vec(any::<bool>(), 0..100),
+            rhs in prop::collection::vec(any::<bool>(), 0..100),
+        ) {
+            let cases: [BinaryOp; 4] = [
+                (|a, b| { a.and(b); }, |l, r| l && r),
+                (|a, b| { a.or(b); }, |l, r| l || r),
+                (|a, b| { a.xor(b); }, |l, r| l ^ r),
+                (|a, b| { a.and_not(b); }, |l, r| l && !r),
+            ];
+            for (op, expected) in cases {
+                let mut bm = from_bools(&lhs);
+                op(&mut bm, from_bools(&rhs));
+                prop_assert_eq!(to_bools(&bm), model(&lhs, &rhs, expected));
+            }
+        }
+
+        #[test]
+        fn unary_ops_match_model(
+            bools in prop::collection::vec(any::<bool>(), 0..100),
+            len in 0usize..120,
+            start in 0usize..120,
+            width in 0usize..60,
+        ) {
+            let bm = from_bools(&bools);
+            prop_assert_eq!(bm.count_ones(), bools.iter().filter(|b| **b).count());
+            prop_assert_eq!(bm.iter_ones().collect::<Vec<_>>(),
+                (0..bools.len()).filter(|i| bools[*i]).collect::<Vec<_>>());
+
+            let mut not = bm.clone();
+            not.not(len);
+            let expected = (0..len).map(|i| !bools.get(i).copied().unwrap_or(false));
+            prop_assert_eq!(to_bools(&not), expected.collect::<Vec<_>>());
+
+            let slice = bm.slice(start..start + width);
+            let expected = (start..start + width).map(|i| bools.get(i).copied().unwrap_or(false));
+            prop_assert_eq!(to_bools(&slice), expected.collect::<Vec<_>>());
+
+            // storage round trip never resurrects rows dropped by a shrinking resize
+            let mut shrunk = not;
+            shrunk.resize(len / 2);
+            let restored = BitMap::from_vec(shrunk.clone().into_vec());
+            prop_assert_eq!(restored.iter_ones().collect::<Vec<_>>(),
+                shrunk.iter_ones().collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn set_and_clear_match_model(
+            bools in prop::collection::vec(any::<bool>(), 0..100),
+            index in 0usize..150,
+        ) {
+            let mut bm = from_bools(&bools);
+            bm.set(index);
+            prop_assert!(bm.contains(index));
+            prop_assert_eq!(bm.len(), bools.len().max(index + 1));
+
+            bm.clear(index);
+            prop_assert!(!bm.contains(index));
+            let mut expected = bools.clone();
+            expected.resize(bm.len(), false);
+            if index < expected.len() {
+                expected[index] = false;
+            }
+            prop_assert_eq!(to_bools(&bm), expected);
+        }
+    }
+}
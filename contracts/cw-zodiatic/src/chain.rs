@@ -0,0 +1,160 @@
+//! Where the records live. With the `zodiatic` feature they are kept by the native module of
+//! a Zodiatic chain; without it the contract keeps them in its own storage and runs on any
+//! CosmWasm chain.
+
+use cosmwasm_std::{Deps, StdError, StdResult, Storage, SubMsg};
+#[cfg(not(feature = "zodiatic"))]
+use cosmwasm_std::{Empty, Order};
+#[cfg(not(feature = "zodiatic"))]
+use cw_storage_plus::{Bound, Map};
+use zodiatic_bindings::{Lunar, ZodiaticMsg};
+#[cfg(feature = "zodiatic")]
+use zodiatic_bindings::{ZodiaticQuerier, ZodiaticQuery};
+
+#[cfg(feature = "zodiatic")]
+use crate::state::{Pending, PENDING};
+
+/// The custom query type of the chain
+#[cfg(feature = "zodiatic")]
+pub type ChainQuery = ZodiaticQuery;
+#[cfg(not(feature = "zodiatic"))]
+pub type ChainQuery = Empty;
+
+/// The custom message type of the chain
+#[cfg(feature = "zodiatic")]
+pub type ChainMsg = ZodiaticMsg;
+#[cfg(not(feature = "zodiatic"))]
+pub type ChainMsg = Empty;
+
+// yyyymmdd -> record, in place of the native module
+#[cfg(not(feature = "zodiatic"))]
+pub const LUNARS: Map<u64, Lunar> = Map::new("lunars");
+
+// id of the reply to a message that the native module rejects
+#[cfg(feature = "zodiatic")]
+pub const SYNC_REPLY_ID: u64 = 1;
+
+/// Returns the record of `yyyymmdd`
+#[cfg(feature = "zodiatic")]
+pub fn lunar(deps: Deps<ChainQuery>, yyyymmdd: u64) -> StdResult<Lunar> {
+    let querier = ZodiaticQuerier::new(&deps.querier);
+    Ok(querier.lunar(yyyymmdd)?.lunar)
+}
+
+#[cfg(not(feature = "zodiatic"))]
+pub fn lunar(deps: Deps<ChainQuery>, yyyymmdd: u64) -> StdResult<Lunar> {
+    LUNARS
+        .may_load(deps.storage, yyyymmdd)?
+        .ok_or_else(|| StdError::not_found(format!("Lunar {}", yyyymmdd)))
+}
+
+/// Returns the record of `yyyymmdd`, or `None` if there is none. Other errors are returned.
+#[cfg(feature = "zodiatic")]
+pub fn may_load_lunar(deps: Deps<ChainQuery>, yyyymmdd: u64) -> StdResult<Option<Lunar>> {
+    // a batch answers a missing day with `None` rather than an error
+    Ok(lunar_batch(deps, vec![yyyymmdd])?.pop().flatten())
+}
+
+#[cfg(not(feature = "zodiatic"))]
+pub fn may_load_lunar(deps: Deps<ChainQuery>, yyyymmdd: u64) -> StdResult<Option<Lunar>> {
+    LUNARS.may_load(deps.storage, yyyymmdd)
+}
+
+/// Returns the record of each of `keys`, in the same order, or `None` where there is none.
+/// An answer without an entry for every key is an error.
+#[cfg(feature = "zodiatic")]
+pub fn lunar_batch(deps: Deps<ChainQuery>, keys: Vec<u64>) -> StdResult<Vec<Option<Lunar>>> {
+    let querier = ZodiaticQuerier::new(&deps.querier);
+    let count = keys.len();
+    let lunars = querier.lunar_batch(keys)?.lunars;
+    if lunars.len() != count {
+        return Err(StdError::generic_err(format!(
+            "Expected {} records in the batch, got {}",
+            count,
+            lunars.len()
+        )));
+    }
+    Ok(lunars)
+}
+
+#[cfg(not(feature = "zodiatic"))]
+pub fn lunar_batch(deps: Deps<ChainQuery>, keys: Vec<u64>) -> StdResult<Vec<Option<Lunar>>> {
+    keys.into_iter()
+        .map(|yyyymmdd| LUNARS.may_load(deps.storage, yyyymmdd))
+        .collect()
+}
+
+/// Returns every record from `start` to `end` (inclusive), earliest first
+#[cfg(feature = "zodiatic")]
+pub fn lunar_range(deps: Deps<ChainQuery>, start: u64, end: u64) -> StdResult<Vec<(u64, Lunar)>> {
+    let querier = ZodiaticQuerier::new(&deps.querier);
+    Ok(querier.lunar_range(start, end)?.lunars)
+}
+
+#[cfg(not(feature = "zodiatic"))]
+pub fn lunar_range(deps: Deps<ChainQuery>, start: u64, end: u64) -> StdResult<Vec<(u64, Lunar)>> {
+    LUNARS
+        .range(
+            deps.storage,
+            Some(Bound::inclusive(start)),
+            Some(Bound::inclusive(end)),
+            Order::Ascending,
+        )
+        .collect()
+}
+
+/// Makes the change `msg` to the records, now that row `row_id` of the index holds it. The
+/// native module gets `msg` in a submessage that is replied to only if the module rejects it,
+/// and the reply then puts back the row, which held `previous` before.
+#[cfg(feature = "zodiatic")]
+pub fn write(
+    storage: &mut dyn Storage,
+    row_id: usize,
+    previous: Option<Lunar>,
+    msg: ZodiaticMsg,
+) -> StdResult<Vec<SubMsg<ChainMsg>>> {
+    let yyyymmdd = match &msg {
+        ZodiaticMsg::CreateLunar { yyyymmdd, .. }
+        | ZodiaticMsg::UpdateLunar { yyyymmdd, .. }
+        | ZodiaticMsg::DeleteLunar { yyyymmdd, .. } => *yyyymmdd,
+    };
+    let pending = Pending {
+        yyyymmdd,
+        row_id: row_id as u32,
+        previous,
+    };
+    PENDING.save(storage, &pending)?;
+    Ok(vec![SubMsg::reply_on_error(msg, SYNC_REPLY_ID)])
+}
+
+/// Makes the change `msg` to the records. They are in the same storage as the index, so the
+/// change needs no confirmation.
+#[cfg(not(feature = "zodiatic"))]
+pub fn write(
+    storage: &mut dyn Storage,
+    _row_id: usize,
+    _previous: Option<Lunar>,
+    msg: ZodiaticMsg,
+) -> StdResult<Vec<SubMsg<ChainMsg>>> {
+    match msg {
+        ZodiaticMsg::CreateLunar {
+            yyyymmdd, lunar, ..
+        }
+        | ZodiaticMsg::UpdateLunar {
+            yyyymmdd, lunar, ..
+        } => LUNARS.save(storage, yyyymmdd, &lunar)?,
+        ZodiaticMsg::DeleteLunar { yyyymmdd, .. } => LUNARS.remove(storage, yyyymmdd),
+    }
+    Ok(vec![])
+}
+
+/// Keeps a record that governance hands to the contract. The native module has it already.
+#[cfg(feature = "zodiatic")]
+pub fn ingest(_storage: &mut dyn Storage, _yyyymmdd: u64, _lunar: &Lunar) -> StdResult<()> {
+    Ok(())
+}
+
+#[cfg(not(feature = "zodiatic"))]
+pub fn ingest(storage: &mut dyn Storage, yyyymmdd: u64, lunar: &Lunar) -> StdResult<()> {
+    LUNARS.save(storage, yyyymmdd, lunar)
+}
@@ -1,33 +1,60 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult,
     Storage,
 };
+#[cfg(feature = "zodiatic")]
+use cosmwasm_std::{Reply, SubMsgResult};
 use cw2::set_contract_version;
+use cw_storage_plus::Bound;
 
 use crate::bitmap::BitMap;
+use crate::chain::{self, ChainMsg, ChainQuery};
+use crate::date::{format_date, local_yyyymmdd, parse_yyyymmdd};
 use crate::error::ContractError;
 use crate::msg::{
-    ExecuteMsg, FindLunarResponse, GetLunarResponse, InstantiateMsg, Predicate, QueryMsg,
+    ExecuteMsg, FindLunarResponse, GetLunarResponse, InstantiateMsg, ListActivitiesResponse,
+    ListFestivalsResponse, LunarDaysResponse, LunarRow, LunarTranslation, Predicate, Preference,
+    QueryMsg, RankLunarResponse, RankedLunar, RowStatus, SolarTermOnResponse, SolarTermsResponse,
+    SudoMsg, TodayResponse,
 };
-use crate::state::{State, INDEX, KEYS, STATE};
+use crate::state::{
+    default_utc_offset, State, ACTIVITIES, ACTIVITY_COUNT, ACTIVITY_NAMES, DELETED, INDEX, KEYS,
+    STATE,
+};
+#[cfg(feature = "zodiatic")]
+use crate::state::{Pending, PENDING};
 
-use zodiatic_bindings::{Lunar, ZodiaticMsg, ZodiaticQuerier, ZodiaticQuery};
+use zodiatic_bindings::{
+    animal_name, festivals, hour_name, resolve_alias, solar_term_on, solar_terms, to_traditional,
+    translate, zodiac_animal, FestivalRule, Language, Lunar, ZodiaticMsg, FESTIVALS,
+    SOLAR_TERM_YEARS,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:cw-zodiatic";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// fields whose values are activities, indexed by their id in ACTIVITIES
+const ACTIVITY_FIELDS: [&str; 2] = ["good_for", "bad_for"];
+
+// pagination info for ListActivities
+const MAX_LIMIT: u32 = 100;
+const DEFAULT_LIMIT: u32 = 30;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut<ZodiaticQuery>,
+    deps: DepsMut<ChainQuery>,
     _env: Env,
     info: MessageInfo,
-    _msg: InstantiateMsg,
-) -> Result<Response, ContractError> {
+    msg: InstantiateMsg,
+) -> Result<Response<ChainMsg>, ContractError> {
+    let utc_offset_minutes = msg.utc_offset_minutes.unwrap_or_else(default_utc_offset);
+    validate_utc_offset(utc_offset_minutes)?;
     let state = State {
         owner: info.sender.clone(),
+        utc_offset_minutes,
     };
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     STATE.save(deps.storage, &state)?;
@@ -39,67 +66,272 @@ pub fn instantiate(
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
-    deps: DepsMut<ZodiaticQuery>,
+    deps: DepsMut<ChainQuery>,
     _env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
-) -> Result<Response<ZodiaticMsg>, ContractError> {
+) -> Result<Response<ChainMsg>, ContractError> {
     match msg {
         ExecuteMsg::CreateLunar { yyyymmdd, lunar } => {
             create_lunar(deps, info.sender, yyyymmdd, lunar)
         }
+        ExecuteMsg::UpdateLunar { yyyymmdd, lunar } => {
+            update_lunar(deps, info.sender, yyyymmdd, lunar)
+        }
+        ExecuteMsg::DeleteLunar { yyyymmdd } => delete_lunar(deps, info.sender, yyyymmdd),
+        ExecuteMsg::SetUtcOffset { utc_offset_minutes } => {
+            set_utc_offset(deps, info.sender, utc_offset_minutes)
+        }
+        ExecuteMsg::AddActivities { activities } => add_activities(deps, info.sender, activities),
+        ExecuteMsg::RenameActivity { id, name } => rename_activity(deps, info.sender, id, name),
     }
 }
 
 pub fn create_lunar(
-    deps: DepsMut<ZodiaticQuery>,
+    deps: DepsMut<ChainQuery>,
     creator: Addr,
     yyyymmdd: u64,
     lunar: Lunar,
-) -> Result<Response<ZodiaticMsg>, ContractError> {
+) -> Result<Response<ChainMsg>, ContractError> {
+    validate_lunar(yyyymmdd, &lunar)?;
+
     let year: u32 = (yyyymmdd / 10000).try_into().unwrap();
-    let keys = KEYS.update(deps.storage, year, |val| -> Result<_, ContractError> {
-        if let Some(mut val) = val {
-            val.push(yyyymmdd);
-            Ok(val)
-        } else {
-            Ok(vec![yyyymmdd])
-        }
+    let mut keys = KEYS.may_load(deps.storage, year)?.unwrap_or_default();
+    if keys.contains(&yyyymmdd) {
+        return Err(ContractError::LunarExists { yyyymmdd });
+    }
+
+    let row_id = keys.len();
+    index_lunar(deps.storage, year, yyyymmdd, &lunar, row_id)?;
+    keys.push(yyyymmdd);
+    KEYS.save(deps.storage, year, &keys)?;
+
+    let msg = ZodiaticMsg::create_lunar(creator.to_string(), yyyymmdd, lunar);
+    Ok(Response::new()
+        .add_attribute("method", "create_lunar")
+        .add_submessages(chain::write(deps.storage, row_id, None, msg)?))
+}
+
+pub fn update_lunar(
+    deps: DepsMut<ChainQuery>,
+    sender: Addr,
+    yyyymmdd: u64,
+    lunar: Lunar,
+) -> Result<Response<ChainMsg>, ContractError> {
+    ensure_owner(deps.storage, &sender)?;
+    validate_lunar(yyyymmdd, &lunar)?;
+
+    let (year, row_id) = find_row(deps.storage, yyyymmdd)?;
+    let previous = chain::may_load_lunar(deps.as_ref(), yyyymmdd)?;
+    unindex_row(deps.storage, year, row_id)?;
+    index_lunar(deps.storage, year, yyyymmdd, &lunar, row_id)?;
+
+    let msg = ZodiaticMsg::update_lunar(sender.to_string(), yyyymmdd, lunar);
+    Ok(Response::new()
+        .add_attribute("method", "update_lunar")
+        .add_submessages(chain::write(deps.storage, row_id, previous, msg)?))
+}
+
+pub fn delete_lunar(
+    deps: DepsMut<ChainQuery>,
+    sender: Addr,
+    yyyymmdd: u64,
+) -> Result<Response<ChainMsg>, ContractError> {
+    ensure_owner(deps.storage, &sender)?;
+
+    let (year, row_id) = find_row(deps.storage, yyyymmdd)?;
+    let previous = chain::may_load_lunar(deps.as_ref(), yyyymmdd)?;
+    unindex_row(deps.storage, year, row_id)?;
+    // keep the row so that the rows after it keep their ids
+    KEYS.update(deps.storage, year, |keys| -> StdResult<_> {
+        let mut keys = keys.unwrap_or_default();
+        keys[row_id] = DELETED;
+        Ok(keys)
     })?;
 
-    let row_id = keys.len() - 1;
+    let msg = ZodiaticMsg::delete_lunar(sender.to_string(), yyyymmdd);
+    Ok(Response::new()
+        .add_attribute("method", "delete_lunar")
+        .add_submessages(chain::write(deps.storage, row_id, previous, msg)?))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(
+    deps: DepsMut<ChainQuery>,
+    _env: Env,
+    msg: SudoMsg,
+) -> Result<Response<ChainMsg>, ContractError> {
+    match msg {
+        SudoMsg::IngestLunar { yyyymmdd, lunar } => ingest_lunar(deps, yyyymmdd, lunar),
+        SudoMsg::RebuildIndex { year } => rebuild_index(deps, year),
+        SudoMsg::AddActivities { activities } => register_activities(deps, activities),
+    }
+}
+
+/// Indexes a record that the chain already has, without sending it back
+pub fn ingest_lunar(
+    deps: DepsMut<ChainQuery>,
+    yyyymmdd: u64,
+    lunar: Lunar,
+) -> Result<Response<ChainMsg>, ContractError> {
+    validate_lunar(yyyymmdd, &lunar)?;
 
-    update_index(deps.storage, year, "good_for", &lunar.good_for, row_id)?;
-    update_index(deps.storage, year, "bad_for", &lunar.bad_for, row_id)?;
+    let year: u32 = (yyyymmdd / 10000).try_into().unwrap();
+    let mut keys = KEYS.may_load(deps.storage, year)?.unwrap_or_default();
+    let row_id = match keys.iter().position(|key| *key == yyyymmdd) {
+        Some(row_id) => {
+            unindex_row(deps.storage, year, row_id)?;
+            row_id
+        }
+        None => {
+            keys.push(yyyymmdd);
+            keys.len() - 1
+        }
+    };
+    index_lunar(deps.storage, year, yyyymmdd, &lunar, row_id)?;
+    KEYS.save(deps.storage, year, &keys)?;
+    chain::ingest(deps.storage, yyyymmdd, &lunar)?;
 
     Ok(Response::new()
-        .add_attribute("method", "create_lunar")
-        .add_message(ZodiaticMsg::CreateLunar {
-            creator: creator.to_string(),
-            yyyymmdd,
-            lunar,
-        }))
+        .add_attribute("method", "ingest_lunar")
+        .add_attribute("yyyymmdd", yyyymmdd.to_string()))
 }
 
-fn get_key(year: u32, field: &str, value: &str) -> String {
-    format!("{}[{}={}]", year, field, value)
+/// Drops the index of `year` and indexes every record that the chain has for it again.
+/// Records that are not valid are left out of the index and reported as `skipped`.
+pub fn rebuild_index(
+    deps: DepsMut<ChainQuery>,
+    year: u32,
+) -> Result<Response<ChainMsg>, ContractError> {
+    let start = u64::from(year) * 10000;
+    let lunars = chain::lunar_range(deps.as_ref(), start, start + 9999)?;
+
+    for (key, _) in index_of_year(deps.storage, year)? {
+        INDEX.remove(deps.storage, key);
+    }
+    let mut keys = vec![];
+    let mut skipped = vec![];
+    for (yyyymmdd, lunar) in &lunars {
+        // a record that fails to index leaves the index untouched
+        match validate_lunar(*yyyymmdd, lunar)
+            .and_then(|_| index_lunar(deps.storage, year, *yyyymmdd, lunar, keys.len()))
+        {
+            Ok(()) => keys.push(*yyyymmdd),
+            Err(ContractError::Std(err)) => return Err(err.into()),
+            Err(_) => skipped.push(yyyymmdd.to_string()),
+        }
+    }
+    if keys.is_empty() {
+        KEYS.remove(deps.storage, year);
+    } else {
+        KEYS.save(deps.storage, year, &keys)?;
+    }
+
+    let mut res = Response::new()
+        .add_attribute("method", "rebuild_index")
+        .add_attribute("year", year.to_string())
+        .add_attribute("indexed", keys.len().to_string());
+    if !skipped.is_empty() {
+        res = res.add_attribute("skipped", skipped.join(","));
+    }
+    Ok(res)
+}
+
+#[cfg(feature = "zodiatic")]
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(
+    deps: DepsMut<ChainQuery>,
+    _env: Env,
+    msg: Reply,
+) -> Result<Response<ChainMsg>, ContractError> {
+    if msg.id != chain::SYNC_REPLY_ID {
+        return Err(ContractError::UnknownReplyId { id: msg.id });
+    }
+    // the native module only replies when it rejects a change
+    let err = match msg.result {
+        SubMsgResult::Err(err) => err,
+        SubMsgResult::Ok(_) => return Err(ContractError::UnexpectedReply { id: msg.id }),
+    };
+    let pending = PENDING.load(deps.storage)?;
+    PENDING.remove(deps.storage);
+    restore_row(deps.storage, &pending)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "reply")
+        .add_attribute("yyyymmdd", pending.yyyymmdd.to_string())
+        .add_attribute("status", "reverted")
+        .add_attribute("error", err))
+}
+
+/// Puts the row of a change that the native module rejected back the way it was, so that
+/// the index never points at a record that the chain does not have
+#[cfg(feature = "zodiatic")]
+fn restore_row(storage: &mut dyn Storage, pending: &Pending) -> Result<(), ContractError> {
+    let year: u32 = (pending.yyyymmdd / 10000).try_into().unwrap();
+    let row_id = pending.row_id as usize;
+    unindex_row(storage, year, row_id)?;
+
+    let mut keys = KEYS.load(storage, year)?;
+    match &pending.previous {
+        Some(lunar) => {
+            index_lunar(storage, year, pending.yyyymmdd, lunar, row_id)?;
+            keys[row_id] = pending.yyyymmdd;
+        }
+        None => keys[row_id] = DELETED,
+    }
+    KEYS.save(storage, year, &keys)?;
+    Ok(())
+}
+
+/// Returns the year and the row id of an indexed day
+fn find_row(storage: &dyn Storage, yyyymmdd: u64) -> Result<(u32, usize), ContractError> {
+    let year =
+        u32::try_from(yyyymmdd / 10000).map_err(|_| ContractError::InvalidDate { yyyymmdd })?;
+    KEYS.may_load(storage, year)?
+        .unwrap_or_default()
+        .iter()
+        .position(|key| *key == yyyymmdd && yyyymmdd != DELETED)
+        .map(|row_id| (year, row_id))
+        .ok_or(ContractError::LunarNotFound { yyyymmdd })
 }
 
-fn update_index(
+/// Sets the bits of `row_id` for every indexed field of `lunar`. Every key is resolved
+/// before anything is written, so an unknown activity leaves the index untouched.
+fn index_lunar(
     storage: &mut dyn Storage,
     year: u32,
-    field: &str,
-    values: &String,
+    yyyymmdd: u64,
+    lunar: &Lunar,
     row_id: usize,
 ) -> Result<(), ContractError> {
-    for value in values.split_ascii_whitespace() {
-        let key = get_key(year, field, value);
-        INDEX.update(storage, key, |bitmap| -> Result<_, ContractError> {
-            let mut bm = if let Some(bitmap) = bitmap {
-                BitMap::from_vec(bitmap)
-            } else {
-                BitMap::new()
-            };
+    let mut fields = vec![
+        ("good_for".to_string(), lunar.good_for.clone()),
+        ("bad_for".to_string(), lunar.bad_for.clone()),
+        ("clash".to_string(), clash_animal(&lunar.clash)),
+        ("sha".to_string(), lunar.sha_direction.clone()),
+        (
+            "solar_term".to_string(),
+            solar_term_on(yyyymmdd).unwrap_or_default().to_string(),
+        ),
+        ("festival".to_string(), festivals(yyyymmdd, lunar).join(" ")),
+    ];
+    for hour in &lunar.hours {
+        // records from the chain may not have been through validate_lunar
+        let name = hour_name(&hour.hour).ok_or_else(|| ContractError::InvalidHour {
+            hour: hour.hour.clone(),
+        })?;
+        fields.push((format!("good_for@{}", name), hour.good_for.clone()));
+        fields.push((format!("bad_for@{}", name), hour.bad_for.clone()));
+        fields.push((format!("clash@{}", name), clash_animal(&hour.clash)));
+    }
+
+    let mut keys = vec![];
+    for (field, values) in &fields {
+        keys.extend(index_keys(storage, year, field, values)?);
+    }
+    for key in keys {
+        INDEX.update(storage, key, |bitmap| -> StdResult<_> {
+            let mut bm = bitmap.map(BitMap::from_vec).unwrap_or_default();
             bm.set(row_id);
             Ok(bm.into_vec())
         })?;
@@ -107,98 +339,725 @@ fn update_index(
     Ok(())
 }
 
+/// Clears the bits of `row_id` in every index bitmap of `year`
+fn unindex_row(storage: &mut dyn Storage, year: u32, row_id: usize) -> StdResult<()> {
+    for (key, bitmap) in index_of_year(storage, year)? {
+        let mut bm = BitMap::from_vec(bitmap);
+        if bm.contains(row_id) {
+            bm.clear(row_id);
+            INDEX.save(storage, key, &bm.into_vec())?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns every index bitmap of `year` with its key
+fn index_of_year(storage: &dyn Storage, year: u32) -> StdResult<Vec<(String, Vec<u32>)>> {
+    // '\\' is the character right after '[', so this is every key starting with "{year}["
+    let start = Bound::inclusive(format!("{}[", year));
+    let end = Bound::exclusive(format!("{}\\", year));
+    INDEX
+        .range(storage, Some(start), Some(end), Order::Ascending)
+        .collect()
+}
+
+/// Checks that `lunar` is a consistent record for `yyyymmdd` before it gets indexed
+fn validate_lunar(yyyymmdd: u64, lunar: &Lunar) -> Result<(), ContractError> {
+    let (year, month, day) =
+        parse_yyyymmdd(yyyymmdd).ok_or(ContractError::InvalidDate { yyyymmdd })?;
+
+    for (field, value) in [
+        ("date", &lunar.date),
+        ("lunar", &lunar.lunar),
+        ("eight_words", &lunar.eight_words),
+        ("god_direction", &lunar.god_direction),
+    ] {
+        if value.trim().is_empty() {
+            return Err(ContractError::EmptyField {
+                field: field.to_string(),
+            });
+        }
+    }
+
+    if lunar.date != format_date(year, month, day) {
+        return Err(ContractError::DateMismatch {
+            yyyymmdd,
+            date: lunar.date.clone(),
+        });
+    }
+
+    // the lunar year starts in January or February, so it is the solar year or the one before
+    let (lunar_year, lunar_month, lunar_day) = lunar.lunar_number;
+    if !(u64::from(year) - 1..=u64::from(year)).contains(&lunar_year)
+        || !(1..=12).contains(&lunar_month)
+        || !(1..=30).contains(&lunar_day)
+    {
+        return Err(ContractError::InvalidLunarNumber {
+            lunar_number: lunar.lunar_number,
+        });
+    }
+
+    validate_activities(&lunar.good_for, &lunar.bad_for)?;
+
+    validate_clash(&lunar.clash)?;
+
+    let mut hours = vec![];
+    for hour in &lunar.hours {
+        let name = hour_name(&hour.hour).ok_or_else(|| ContractError::InvalidHour {
+            hour: hour.hour.clone(),
+        })?;
+        if hours.contains(&name) {
+            return Err(ContractError::DuplicateHour {
+                hour: name.to_string(),
+            });
+        }
+        hours.push(name);
+        validate_activities(&hour.good_for, &hour.bad_for)?;
+        validate_clash(&hour.clash)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the animal of a clash as indexed, e.g. 虎 for "沖虎"
+fn clash_animal(clash: &str) -> String {
+    animal_name(clash).map_or_else(|| clash.to_string(), String::from)
+}
+
+/// Checks that a clash, if any, is one of the zodiac animals
+fn validate_clash(clash: &str) -> Result<(), ContractError> {
+    if !clash.is_empty() && animal_name(clash).is_none() {
+        return Err(ContractError::InvalidAnimal {
+            animal: clash.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn validate_activities(good_for: &str, bad_for: &str) -> Result<(), ContractError> {
+    let good_for = good_for
+        .split_ascii_whitespace()
+        .map(to_traditional)
+        .collect::<Vec<_>>();
+    if let Some(activity) = bad_for
+        .split_ascii_whitespace()
+        .map(to_traditional)
+        .find(|activity| good_for.contains(activity))
+    {
+        return Err(ContractError::OverlappingActivity { activity });
+    }
+    Ok(())
+}
+
+fn ensure_owner(storage: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    let state = STATE.load(storage)?;
+    if *sender != state.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+pub fn set_utc_offset(
+    deps: DepsMut<ChainQuery>,
+    sender: Addr,
+    utc_offset_minutes: i32,
+) -> Result<Response<ChainMsg>, ContractError> {
+    ensure_owner(deps.storage, &sender)?;
+    validate_utc_offset(utc_offset_minutes)?;
+    STATE.update(deps.storage, |mut state| -> StdResult<_> {
+        state.utc_offset_minutes = utc_offset_minutes;
+        Ok(state)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_utc_offset")
+        .add_attribute("utc_offset_minutes", utc_offset_minutes.to_string()))
+}
+
+/// Timezones range from UTC-12 to UTC+14
+fn validate_utc_offset(utc_offset_minutes: i32) -> Result<(), ContractError> {
+    if !(-12 * 60..=14 * 60).contains(&utc_offset_minutes) {
+        return Err(ContractError::InvalidUtcOffset { utc_offset_minutes });
+    }
+    Ok(())
+}
+
+pub fn add_activities(
+    deps: DepsMut<ChainQuery>,
+    sender: Addr,
+    activities: Vec<String>,
+) -> Result<Response<ChainMsg>, ContractError> {
+    ensure_owner(deps.storage, &sender)?;
+    register_activities(deps, activities)
+}
+
+/// Adds activities to the dictionary, skipping known ones, on behalf of the owner or the chain
+pub fn register_activities(
+    deps: DepsMut<ChainQuery>,
+    activities: Vec<String>,
+) -> Result<Response<ChainMsg>, ContractError> {
+    let activities = activities
+        .iter()
+        .map(|activity| to_traditional(activity))
+        .collect::<Vec<_>>();
+    for activity in &activities {
+        validate_activity_name(activity)?;
+    }
+
+    let mut added = 0;
+    for activity in activities {
+        if !ACTIVITIES.has(deps.storage, &activity) {
+            add_activity(deps.storage, &activity)?;
+            added += 1;
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "add_activities")
+        .add_attribute("added", added.to_string()))
+}
+
+pub fn rename_activity(
+    deps: DepsMut<ChainQuery>,
+    sender: Addr,
+    id: u16,
+    name: String,
+) -> Result<Response<ChainMsg>, ContractError> {
+    ensure_owner(deps.storage, &sender)?;
+    let name = to_traditional(&name);
+    validate_activity_name(&name)?;
+    // a former name of the same activity may be taken back
+    if matches!(ACTIVITIES.may_load(deps.storage, &name)?, Some(other) if other != id) {
+        return Err(ContractError::ActivityExists { activity: name });
+    }
+    if !ACTIVITY_NAMES.has(deps.storage, id) {
+        return Err(ContractError::UnknownActivityId { id });
+    }
+
+    // index keys refer to the id, so only the dictionary needs to change. The old name keeps
+    // the id, as the records on chain still use it.
+    ACTIVITIES.save(deps.storage, &name, &id)?;
+    ACTIVITY_NAMES.save(deps.storage, id, &name)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "rename_activity")
+        .add_attribute("id", id.to_string())
+        .add_attribute("name", name))
+}
+
+fn get_key(year: u32, field: &str, value: &str) -> String {
+    format!("{}[{}={}]", year, field, value)
+}
+
+/// Returns the field as used in index keys. A field can be narrowed to a double-hour with
+/// `@`, given by name or by its earthly branch, e.g. `good_for@子` is `good_for@子時`.
+/// Returns `None` if the double-hour is unknown.
+fn index_field(field: &str) -> Option<String> {
+    match field.split_once('@') {
+        Some((field, hour)) => hour_name(hour).map(|hour| format!("{}@{}", field, hour)),
+        None => Some(field.to_string()),
+    }
+}
+
+fn is_activity_field(field: &str) -> bool {
+    let field = field.split_once('@').map_or(field, |(field, _)| field);
+    ACTIVITY_FIELDS.contains(&field)
+}
+
+/// Returns the id of an activity being indexed, which must be in the dictionary in either script
+fn activity_id(storage: &dyn Storage, field: &str, activity: &str) -> Result<u16, ContractError> {
+    ACTIVITIES
+        .may_load(storage, &to_traditional(activity))?
+        .ok_or_else(|| ContractError::UnknownActivity {
+            field: field.to_string(),
+            activity: activity.to_string(),
+        })
+}
+
+/// Assigns the next id to a new activity
+/// Checks that `activity` can be told apart in records and predicates: they are split on
+/// whitespace, and a number is read as an activity id
+fn validate_activity_name(activity: &str) -> Result<(), ContractError> {
+    if activity.is_empty() {
+        return Err(ContractError::EmptyActivity {});
+    }
+    if activity.chars().any(char::is_whitespace) {
+        return Err(ContractError::ActivityWithWhitespace {
+            activity: activity.to_string(),
+        });
+    }
+    if activity.parse::<u16>().is_ok() {
+        return Err(ContractError::NumericActivity {
+            activity: activity.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn add_activity(storage: &mut dyn Storage, activity: &str) -> Result<u16, ContractError> {
+    let id = ACTIVITY_COUNT.may_load(storage)?.unwrap_or_default();
+    let next_id = id.checked_add(1).ok_or(ContractError::ActivityLimit {})?;
+    ACTIVITIES.save(storage, activity, &id)?;
+    ACTIVITY_NAMES.save(storage, id, &activity.to_string())?;
+    ACTIVITY_COUNT.save(storage, &next_id)?;
+    Ok(id)
+}
+
+/// Maps a predicate value to the value used in index keys, accepting an activity, its
+/// English name or pinyin, or its id for activity fields. Returns `None` if the activity
+/// is not in the dictionary.
+///
+/// Values are normalized to traditional characters, as they are when indexed.
+fn lookup_index_value(
+    storage: &dyn Storage,
+    field: &str,
+    value: &str,
+) -> StdResult<Option<String>> {
+    let value = to_traditional(value);
+    if field.split_once('@').map_or(field, |(field, _)| field) == "clash" {
+        return Ok(Some(clash_animal(&value)));
+    }
+    if !is_activity_field(field) {
+        return Ok(Some(value));
+    }
+    if let Ok(id) = value.parse::<u16>() {
+        return Ok(Some(id.to_string()));
+    }
+    let value = resolve_alias(&value).map(String::from).unwrap_or(value);
+    Ok(ACTIVITIES
+        .may_load(storage, &value)?
+        .map(|id| id.to_string()))
+}
+
+/// Returns the INDEX keys of the whitespace-separated `values` of `field`
+fn index_keys(
+    storage: &dyn Storage,
+    year: u32,
+    field: &str,
+    values: &str,
+) -> Result<Vec<String>, ContractError> {
+    values
+        .split_ascii_whitespace()
+        .map(|value| {
+            if is_activity_field(field) {
+                let id = activity_id(storage, field, value)?;
+                Ok(get_key(year, field, &id.to_string()))
+            } else {
+                Ok(get_key(year, field, &to_traditional(value)))
+            }
+        })
+        .collect()
+}
+
 fn _try_get_index(
-    deps: Deps<ZodiaticQuery>,
+    deps: Deps<ChainQuery>,
     year: u32,
     field: &str,
     value: &str,
 ) -> Result<Option<BitMap>, ContractError> {
-    let key = get_key(year, field, value);
-    let vec = INDEX.may_load(deps.storage, key)?;
-    Ok(vec.map(|vec| BitMap::from_vec(vec)))
+    let field = match index_field(field) {
+        Some(field) => field,
+        None => return Ok(None),
+    };
+    match lookup_index_value(deps.storage, &field, value)? {
+        Some(value) => {
+            let key = get_key(year, &field, &value);
+            let vec = INDEX.may_load(deps.storage, key)?;
+            Ok(vec.map(BitMap::from_vec))
+        }
+        None => Ok(None),
+    }
 }
 
-fn _get_index_or_default(deps: Deps<ZodiaticQuery>, year: u32, field: &str, value: &str) -> BitMap {
+fn _get_index_or_default(deps: Deps<ChainQuery>, year: u32, field: &str, value: &str) -> BitMap {
     _try_get_index(deps, year, field, value)
         .unwrap()
         .unwrap_or_default()
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps<ZodiaticQuery>, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps<ChainQuery>, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::GetLunar { yyyymmdd } => to_binary(&get_lunar(deps, yyyymmdd)?),
-        QueryMsg::FindLunar { year, predicates } => to_binary(&find_lunar(deps, year, predicates)?),
+        QueryMsg::Today { lang } => to_binary(&today(deps, env, 0, lang)?),
+        QueryMsg::Tomorrow { lang } => to_binary(&today(deps, env, 1, lang)?),
+        QueryMsg::GetLunar { yyyymmdd, lang } => to_binary(&get_lunar(deps, yyyymmdd, lang)?),
+        QueryMsg::FindLunar {
+            year,
+            predicates,
+            strict,
+            lang,
+        } => to_binary(&find_lunar(
+            deps,
+            year,
+            predicates,
+            strict.unwrap_or_default(),
+            lang,
+        )?),
+        QueryMsg::NextLunar {
+            after,
+            predicates,
+            limit,
+        } => to_binary(&next_lunar(deps, after, predicates, limit)?),
+        QueryMsg::PrevLunar {
+            before,
+            predicates,
+            limit,
+        } => to_binary(&prev_lunar(deps, before, predicates, limit)?),
+        QueryMsg::RankLunar {
+            start,
+            end,
+            wanted,
+            unwanted,
+            limit,
+        } => to_binary(&rank_lunar(deps, start, end, wanted, unwanted, limit)?),
+        QueryMsg::ListActivities { start_after, limit } => {
+            to_binary(&list_activities(deps, start_after, limit)?)
+        }
+        QueryMsg::GetSolarTerms { year } => to_binary(&get_solar_terms(year)?),
+        QueryMsg::SolarTermOn { yyyymmdd } => to_binary(&SolarTermOnResponse {
+            solar_term: solar_term_on(yyyymmdd).map(String::from),
+        }),
+        QueryMsg::ListFestivals { year } => to_binary(&list_festivals(deps, year)?),
+    }
+}
+
+/// Lists the festivals of `year`. Those on a solar term are computed, the others are read
+/// from the index, which only has the days that have a record.
+fn list_festivals(deps: Deps<ChainQuery>, year: u32) -> StdResult<ListFestivalsResponse> {
+    let keys = KEYS.may_load(deps.storage, year)?.unwrap_or_default();
+    let terms = solar_terms(year).unwrap_or_default();
+    let mut festivals = vec![];
+    for (name, rule) in FESTIVALS {
+        if let FestivalRule::SolarTerm(term) = rule {
+            festivals.extend(
+                terms
+                    .iter()
+                    .filter(|(name, _)| name == term)
+                    .map(|(_, yyyymmdd)| (name.to_string(), *yyyymmdd)),
+            );
+            continue;
+        }
+        let rows = INDEX
+            .may_load(deps.storage, get_key(year, "festival", name))?
+            .map(BitMap::from_vec)
+            .unwrap_or_default();
+        festivals.extend(
+            rows.iter_ones()
+                .filter_map(|row| keys.get(row))
+                .map(|yyyymmdd| (name.to_string(), *yyyymmdd)),
+        );
     }
+    festivals.sort_by_key(|(_, yyyymmdd)| *yyyymmdd);
+
+    Ok(ListFestivalsResponse { festivals })
+}
+
+fn get_solar_terms(year: u32) -> StdResult<SolarTermsResponse> {
+    let terms = solar_terms(year).ok_or_else(|| {
+        StdError::generic_err(format!(
+            "Solar terms are only known from {} to {}",
+            SOLAR_TERM_YEARS.start(),
+            SOLAR_TERM_YEARS.end()
+        ))
+    })?;
+    Ok(SolarTermsResponse {
+        terms: terms
+            .into_iter()
+            .map(|(name, yyyymmdd)| (name.to_string(), yyyymmdd))
+            .collect(),
+    })
+}
+
+fn list_activities(
+    deps: Deps<ChainQuery>,
+    start_after: Option<u16>,
+    limit: Option<u32>,
+) -> StdResult<ListActivitiesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let activities = ACTIVITY_NAMES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ListActivitiesResponse { activities })
 }
 
-fn get_lunar(deps: Deps<ZodiaticQuery>, yyyymmdd: u64) -> StdResult<GetLunarResponse> {
-    let querier = ZodiaticQuerier::new(&deps.querier);
-    let response = querier.lunar(yyyymmdd)?;
+fn get_lunar(
+    deps: Deps<ChainQuery>,
+    yyyymmdd: u64,
+    lang: Option<Language>,
+) -> StdResult<GetLunarResponse> {
+    let lunar = chain::lunar(deps, yyyymmdd)?;
 
     Ok(GetLunarResponse {
-        lunar: response.lunar,
+        translation: lang.map(|lang| translate_lunar(&lunar, lang)),
+        lunar,
+    })
+}
+
+/// Returns the record of the local date `days_ahead` days after the block time
+fn today(
+    deps: Deps<ChainQuery>,
+    env: Env,
+    days_ahead: i64,
+    lang: Option<Language>,
+) -> StdResult<TodayResponse> {
+    let state = STATE.load(deps.storage)?;
+    let yyyymmdd = local_yyyymmdd(env.block.time, state.utc_offset_minutes, days_ahead);
+    let lunar = chain::lunar(deps, yyyymmdd)?;
+
+    Ok(TodayResponse {
+        yyyymmdd,
+        translation: lang.map(|lang| translate_lunar(&lunar, lang)),
+        lunar,
     })
 }
 
-fn match_any(deps: Deps<ZodiaticQuery>, year: u32, any_of: Vec<(String, String)>) -> BitMap {
-    let mut bm = BitMap::new();
+/// Translates the activities of `lunar`, keeping those without a translation as they are
+fn translate_lunar(lunar: &Lunar, lang: Language) -> LunarTranslation {
+    let translate_all = |activities: &str| {
+        activities
+            .split_ascii_whitespace()
+            .map(|activity| translate(activity, lang).unwrap_or(activity).to_string())
+            .collect()
+    };
+    LunarTranslation {
+        good_for: translate_all(&lunar.good_for),
+        bad_for: translate_all(&lunar.bad_for),
+    }
+}
+
+fn match_each(deps: Deps<ChainQuery>, year: u32, any_of: &[(String, String)]) -> Vec<BitMap> {
     any_of
         .iter()
         .map(|(field, value)| _get_index_or_default(deps, year, field, value))
-        .for_each(|next| {
-            bm.or(next);
-        });
+        .collect()
+}
+
+fn union(rows: usize, bitmaps: Vec<BitMap>) -> BitMap {
+    let mut bm = BitMap::zeros(rows);
+    bitmaps.into_iter().for_each(|next| {
+        bm.or(next);
+    });
     bm
 }
 
-fn try_find_lunar(
-    deps: Deps<ZodiaticQuery>,
-    year: u32,
-    predicates: Vec<Predicate>,
-) -> Option<Vec<u64>> {
+/// Rows of a year that satisfy a list of predicates
+struct Matches {
+    keys: Vec<u64>,
+    rows: BitMap,
+    /// `(field, value)` pairs of `And` predicates, with the rows each of them selects
+    pairs: Vec<((String, String), BitMap)>,
+}
+
+impl Matches {
+    /// Returns the matching rows with their keys
+    fn iter(&self) -> impl Iterator<Item = (usize, u64)> + '_ {
+        self.rows.iter_ones().map(|i| (i, self.keys[i]))
+    }
+
+    /// Returns the `And` pairs that row `i` satisfies
+    fn explain(&self, i: usize) -> Vec<(String, String)> {
+        self.pairs
+            .iter()
+            .filter(|(_, bm)| bm.contains(i))
+            .map(|(pair, _)| pair.clone())
+            .collect()
+    }
+}
+
+fn match_rows(deps: Deps<ChainQuery>, year: u32, predicates: Vec<Predicate>) -> Option<Matches> {
     match KEYS.load(deps.storage, year) {
         Ok(keys) => {
-            let mut bv = BitMap::ones(366);
+            let rows = keys.len();
+            let mut bv = BitMap::ones(rows);
+            keys.iter()
+                .enumerate()
+                .filter(|(_, key)| **key == DELETED)
+                .for_each(|(row_id, _)| {
+                    bv.clear(row_id);
+                });
+            let mut pairs = vec![];
 
             for p in predicates {
                 match p {
                     Predicate::And(any_of) => {
-                        bv.and(match_any(deps, year, any_of));
+                        let bitmaps = match_each(deps, year, &any_of);
+                        bv.and(union(rows, bitmaps.clone()));
+                        pairs.extend(any_of.into_iter().zip(bitmaps));
                     }
                     Predicate::AndNot(any_of) => {
-                        bv.xor(match_any(deps, year, any_of));
+                        bv.and_not(union(rows, match_each(deps, year, &any_of)));
+                    }
+                    Predicate::NotClashingWith { birth_year } => {
+                        let animal = zodiac_animal(birth_year);
+                        bv.and_not(_get_index_or_default(deps, year, "clash", animal));
                     }
                 };
             }
-            Some(
-                bv.iter_ones()
-                    .map(|i| keys.get(i).unwrap())
-                    .copied()
-                    .collect::<Vec<_>>(),
-            )
+            Some(Matches {
+                keys,
+                rows: bv,
+                pairs,
+            })
         }
         _ => None,
     }
 }
 
+fn next_lunar(
+    deps: Deps<ChainQuery>,
+    after: u64,
+    predicates: Vec<Predicate>,
+    limit: Option<u32>,
+) -> StdResult<LunarDaysResponse> {
+    walk_lunar(deps, after, Order::Ascending, predicates, limit)
+}
+
+fn prev_lunar(
+    deps: Deps<ChainQuery>,
+    before: u64,
+    predicates: Vec<Predicate>,
+    limit: Option<u32>,
+) -> StdResult<LunarDaysResponse> {
+    walk_lunar(deps, before, Order::Descending, predicates, limit)
+}
+
+/// Walks the indexed years from the year of `from` in the given order, collecting days
+/// strictly after (ascending) or before (descending) `from` that satisfy `predicates`,
+/// until `limit` (default 1) are found
+fn walk_lunar(
+    deps: Deps<ChainQuery>,
+    from: u64,
+    order: Order,
+    predicates: Vec<Predicate>,
+    limit: Option<u32>,
+) -> StdResult<LunarDaysResponse> {
+    let limit = limit.unwrap_or(1).min(MAX_LIMIT) as usize;
+    let year = Bound::inclusive(u32::try_from(from / 10000).unwrap_or(u32::MAX));
+    let years = match order {
+        Order::Ascending => KEYS.keys(deps.storage, Some(year), None, order),
+        Order::Descending => KEYS.keys(deps.storage, None, Some(year), order),
+    };
+
+    let mut result = vec![];
+    for year in years {
+        let mut days = match_rows(deps, year?, predicates.clone())
+            .map(|matches| matches.iter().map(|(_, key)| key).collect::<Vec<_>>())
+            .unwrap_or_default();
+        days.sort_unstable();
+        match order {
+            Order::Ascending => days.retain(|yyyymmdd| *yyyymmdd > from),
+            Order::Descending => {
+                days.retain(|yyyymmdd| *yyyymmdd < from);
+                days.reverse();
+            }
+        }
+
+        result.extend(days.into_iter().take(limit - result.len()));
+        if result.len() >= limit {
+            break;
+        }
+    }
+
+    Ok(LunarDaysResponse { result })
+}
+
+fn rank_lunar(
+    deps: Deps<ChainQuery>,
+    start: u64,
+    end: u64,
+    wanted: Vec<Preference>,
+    unwanted: Vec<Preference>,
+    limit: Option<u32>,
+) -> StdResult<RankLunarResponse> {
+    if start > end {
+        return Err(StdError::generic_err(format!(
+            "Invalid range {}..={}",
+            start, end
+        )));
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_year = u32::try_from(start / 10000).unwrap_or(u32::MAX);
+    let end_year = u32::try_from(end / 10000).unwrap_or(u32::MAX);
+
+    let mut result = vec![];
+    for item in KEYS.range(
+        deps.storage,
+        Some(Bound::inclusive(start_year)),
+        Some(Bound::inclusive(end_year)),
+        Order::Ascending,
+    ) {
+        let (year, keys) = item?;
+        let mut scores = vec![0i64; keys.len()];
+
+        let weighted = wanted
+            .iter()
+            .map(|p| (p, i64::from(p.weight)))
+            .chain(unwanted.iter().map(|p| (p, -i64::from(p.weight))));
+        for (preference, weight) in weighted {
+            let mut bm = _get_index_or_default(deps, year, &preference.field, &preference.value);
+            bm.resize(scores.len());
+            bm.iter_ones().for_each(|i| scores[i] += weight);
+        }
+
+        result.extend(
+            keys.into_iter()
+                .zip(scores)
+                .filter(|(yyyymmdd, _)| *yyyymmdd != DELETED && (start..=end).contains(yyyymmdd))
+                .map(|(yyyymmdd, score)| RankedLunar { yyyymmdd, score }),
+        );
+    }
+
+    // best first, earliest first among equals
+    result.sort_by(|a, b| b.score.cmp(&a.score).then(a.yyyymmdd.cmp(&b.yyyymmdd)));
+    result.truncate(limit);
+
+    Ok(RankLunarResponse { result })
+}
+
 fn find_lunar(
-    deps: Deps<ZodiaticQuery>,
+    deps: Deps<ChainQuery>,
     year: u32,
     predicates: Vec<Predicate>,
+    strict: bool,
+    lang: Option<Language>,
 ) -> StdResult<FindLunarResponse> {
-    let mut result: Vec<Lunar> = vec![];
-    let keys = try_find_lunar(deps, year, predicates);
-    let querier = ZodiaticQuerier::new(&deps.querier);
-
-    match keys {
-        Some(keys) => {
-            keys.iter().for_each(|yyyymmdd| {
-                let res = querier.lunar(*yyyymmdd);
-                if let Ok(res) = res {
-                    result.push(res.lunar);
-                }
-            });
+    let mut result: Vec<LunarRow> = vec![];
+    let matches = match_rows(deps, year, predicates);
+
+    match matches {
+        Some(matches) => {
+            // fetch every record in one round trip
+            let keys = matches.iter().map(|(_, key)| key).collect::<Vec<_>>();
+            let lunars = match chain::lunar_batch(deps, keys.clone()) {
+                Ok(lunars) => lunars.into_iter().map(Ok).collect(),
+                Err(err) if strict => return Err(err),
+                // one failing day fails the batch, so ask for each day to find out which
+                Err(_) => keys
+                    .iter()
+                    .map(|yyyymmdd| chain::may_load_lunar(deps, *yyyymmdd))
+                    .collect::<Vec<_>>(),
+            };
+            for ((i, yyyymmdd), lunar) in matches.iter().zip(lunars) {
+                let (status, lunar) = match lunar {
+                    Ok(Some(lunar)) => (RowStatus::Found, Some(lunar)),
+                    Ok(None) if strict => {
+                        return Err(StdError::not_found(format!("Lunar {}", yyyymmdd)))
+                    }
+                    Ok(None) => (RowStatus::Missing, None),
+                    Err(_) => (RowStatus::Unavailable, None),
+                };
+                result.push(LunarRow {
+                    yyyymmdd,
+                    status,
+                    translation: lang
+                        .and_then(|lang| lunar.as_ref().map(|lunar| translate_lunar(lunar, lang))),
+                    lunar,
+                    matched: matches.explain(i),
+                });
+            }
             Ok(FindLunarResponse { result })
         }
         _ => Err(StdError::not_found(year.to_string())),
@@ -209,15 +1068,95 @@ fn find_lunar(
 mod tests {
 
     use super::*;
-    use cosmwasm_std::coins;
     use cosmwasm_std::testing::{mock_env, mock_info};
-    use zodiatic_bindings_test::mock::mock_dependencies;
+    use cosmwasm_std::{coins, from_binary, Timestamp};
+    use zodiatic_bindings::LunarHour;
+
+    #[cfg(not(feature = "zodiatic"))]
+    use cosmwasm_std::testing::mock_dependencies;
+    #[cfg(feature = "zodiatic")]
+    use {
+        cosmwasm_std::{CosmosMsg, Event, ReplyOn, SubMsgResponse},
+        cw_multi_test::{ContractWrapper, Executor},
+        std::time::Duration,
+        zodiatic_bindings::{Params, ZodiaticQuerier, ZodiaticQuery},
+        zodiatic_bindings_test::mock::{
+            mock_dependencies, mock_dependencies_with_custom_querier, Failure, MockZodiaticQuerier,
+        },
+        zodiatic_bindings_test::{ZodiaticApp, ZodiaticAppBuilder, ZodiaticModule, ZodiaticSudo},
+    };
+
+    /// Instantiates the contract on a mock chain as "creator" and allows the
+    /// whitespace-separated activities
+    #[cfg(feature = "zodiatic")]
+    fn setup_app(activities: &str) -> (ZodiaticApp, Addr) {
+        setup_app_with(ZodiaticAppBuilder::new(), activities)
+    }
+
+    /// Like `setup_app`, on the chain that `builder` builds
+    #[cfg(feature = "zodiatic")]
+    fn setup_app_with(builder: ZodiaticAppBuilder, activities: &str) -> (ZodiaticApp, Addr) {
+        let mut app = builder.build();
+        let contract = ContractWrapper::new(execute, instantiate, query)
+            .with_reply(reply)
+            .with_sudo(sudo);
+        let code_id = app.store_code(Box::new(contract));
+        let creator = Addr::unchecked("creator");
+        let msg = InstantiateMsg {
+            utc_offset_minutes: None,
+        };
+        let contract = app
+            .instantiate_contract(code_id, creator.clone(), &msg, &[], "zodiatic", None)
+            .unwrap();
+        let msg = ExecuteMsg::AddActivities {
+            activities: activities.split_whitespace().map(String::from).collect(),
+        };
+        app.execute_contract(creator, contract.clone(), &msg, &[])
+            .unwrap();
+        (app, contract)
+    }
+
+    fn try_find_lunar(
+        deps: Deps<ChainQuery>,
+        year: u32,
+        predicates: Vec<Predicate>,
+    ) -> Option<Vec<u64>> {
+        match_rows(deps, year, predicates)
+            .map(|matches| matches.iter().map(|(_, key)| key).collect())
+    }
+
+    /// Instantiates the contract as "creator" and allows the whitespace-separated activities
+    fn setup(mut deps: DepsMut<ChainQuery>, activities: &str) {
+        let info = mock_info("creator", &[]);
+        let msg = InstantiateMsg {
+            utc_offset_minutes: None,
+        };
+        instantiate(deps.branch(), mock_env(), info.clone(), msg).unwrap();
+        let activities = activities.split_whitespace().map(String::from).collect();
+        add_activities(deps, info.sender, activities).unwrap();
+    }
+
+    /// A valid record for `yyyymmdd` with the given activities
+    fn lunar(yyyymmdd: u64, good_for: &str, bad_for: &str) -> Lunar {
+        let (year, month, day) = parse_yyyymmdd(yyyymmdd).unwrap();
+        Lunar::new(
+            &format_date(year, month, day),
+            (u64::from(year) - 1, 12, 1),
+            "二零二一 辛醜[牛]年 臘月大初一",
+            "辛醜 庚子 甲寅 甲子",
+            "喜神東北 財神東北 福神正北 陽貴西南 陰貴東北",
+            good_for,
+            bad_for,
+        )
+    }
 
     #[test]
     fn proper_initialization() {
         let mut deps = mock_dependencies();
 
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            utc_offset_minutes: None,
+        };
         let info = mock_info("creator", &coins(1000, "earth"));
 
         // we can just call .unwrap() to assert this was a success
@@ -228,11 +1167,17 @@ mod tests {
     #[test]
     fn test_create_lunar() {
         let mut deps = mock_dependencies();
+        setup(
+            deps.as_mut(),
+            "沐浴 上表章 上官 進人口 豎柱上梁 開市 納財 掃舍宇 牧養 破土 安葬 啟攢 慶賜 解除 訴訟 \
+             施恩 納畜 招賢 祭祀 冠帶 修置產室 開渠 穿井 安碓磑 平治道塗 破屋壞垣 畋獵 苫蓋 \
+             遠回 乘船渡水 補垣 取魚 築堤防",
+        );
 
         create_lunar(
             deps.as_mut(),
             Addr::unchecked("creator"),
-            220101,
+            20220101,
             Lunar::new(
                 "2022-01-01",
                 (2021, 11, 29),
@@ -248,7 +1193,7 @@ mod tests {
         create_lunar(
             deps.as_mut(),
             Addr::unchecked("creator"),
-            220131,
+            20220131,
             Lunar::new(
                 "2022-01-31",
                 (2021, 12, 29),
@@ -263,7 +1208,7 @@ mod tests {
 
         let res = try_find_lunar(
             deps.as_ref(),
-            22,
+            2022,
             vec![
                 Predicate::and(vec!["good_for=慶賜", "good_for=安葬"]),
                 Predicate::and_not(vec!["bad_for=乘船渡水"]),
@@ -272,6 +1217,1419 @@ mod tests {
         .unwrap();
 
         assert_eq!(1, res.len());
-        assert_eq!(220131, res[0]);
+        assert_eq!(20220131, res[0]);
+    }
+
+    #[test]
+    fn test_activity_dictionary() {
+        let mut deps = mock_dependencies();
+        let info = mock_info("creator", &[]);
+        setup(deps.as_mut(), "嫁娶 開市 安葬");
+
+        let lunar = lunar(20220101, "嫁娶 開市", "安葬");
+        create_lunar(deps.as_mut(), info.sender.clone(), 20220101, lunar).unwrap();
+
+        // ids are assigned in order of addition and shared across fields
+        let res = list_activities(deps.as_ref(), None, None).unwrap();
+        assert_eq!(
+            vec![
+                (0, "嫁娶".to_string()),
+                (1, "開市".to_string()),
+                (2, "安葬".to_string())
+            ],
+            res.activities
+        );
+        let res = list_activities(deps.as_ref(), Some(0), Some(1)).unwrap();
+        assert_eq!(vec![(1, "開市".to_string())], res.activities);
+
+        // either the activity or its id selects the same rows
+        let by_name = try_find_lunar(
+            deps.as_ref(),
+            2022,
+            vec![Predicate::and(vec!["good_for=開市"])],
+        );
+        let by_id = try_find_lunar(
+            deps.as_ref(),
+            2022,
+            vec![Predicate::and(vec!["good_for=1"])],
+        );
+        assert_eq!(Some(vec![20220101]), by_name);
+        assert_eq!(by_name, by_id);
+
+        // renaming is owner only and keeps the index intact
+        let err = rename_activity(deps.as_mut(), Addr::unchecked("anyone"), 1, "開張".into())
+            .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let err =
+            rename_activity(deps.as_mut(), info.sender.clone(), 1, "嫁娶".into()).unwrap_err();
+        assert!(matches!(err, ContractError::ActivityExists { .. }));
+        let err =
+            rename_activity(deps.as_mut(), info.sender.clone(), 9, "開張".into()).unwrap_err();
+        assert!(matches!(err, ContractError::UnknownActivityId { id: 9 }));
+
+        rename_activity(deps.as_mut(), info.sender.clone(), 1, "開張".into()).unwrap();
+        let res = try_find_lunar(
+            deps.as_ref(),
+            2022,
+            vec![Predicate::and(vec!["good_for=開張"])],
+        );
+        assert_eq!(Some(vec![20220101]), res);
+        // the record still says 開市, so that name still selects it
+        let res = try_find_lunar(
+            deps.as_ref(),
+            2022,
+            vec![Predicate::and(vec!["good_for=開市"])],
+        );
+        assert_eq!(Some(vec![20220101]), res);
+        let res = list_activities(deps.as_ref(), Some(0), Some(1)).unwrap();
+        assert_eq!(vec![(1, "開張".to_string())], res.activities);
+        // and it may be taken back
+        rename_activity(deps.as_mut(), info.sender.clone(), 1, "開市".into()).unwrap();
+
+        // names that records or predicates could not tell apart are rejected
+        for (name, expected) in [
+            ("", "Activity name must not be empty"),
+            ("嫁 娶", "Activity \"嫁 娶\" must not contain whitespace"),
+            (
+                "7",
+                "Activity \"7\" must not be a number, which would read as an id",
+            ),
+        ] {
+            let err = add_activities(
+                deps.as_mut(),
+                info.sender.clone(),
+                vec!["祭祀".into(), name.into()],
+            )
+            .unwrap_err();
+            assert_eq!(expected, err.to_string());
+            let err =
+                rename_activity(deps.as_mut(), info.sender.clone(), 1, name.into()).unwrap_err();
+            assert_eq!(expected, err.to_string());
+        }
+        // nothing is added when one name is rejected
+        let res = list_activities(deps.as_ref(), None, None).unwrap();
+        assert_eq!(3, res.activities.len());
+    }
+
+    #[test]
+    fn test_unknown_activity() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut(), "嫁娶");
+
+        let lunar = lunar(20220101, "嫁娶", "嫁取");
+        let err = create_lunar(
+            deps.as_mut(),
+            Addr::unchecked("creator"),
+            20220101,
+            lunar.clone(),
+        )
+        .unwrap_err();
+        assert_eq!("Unknown activity \"嫁取\" in bad_for", err.to_string());
+
+        let err = add_activities(
+            deps.as_mut(),
+            Addr::unchecked("anyone"),
+            vec!["嫁取".into()],
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+
+        // known activities are skipped when extending the dictionary
+        let res = add_activities(
+            deps.as_mut(),
+            Addr::unchecked("creator"),
+            vec!["嫁娶".into(), "嫁取".into()],
+        )
+        .unwrap();
+        assert_eq!(
+            ("added", "1"),
+            (
+                res.attributes[1].key.as_str(),
+                res.attributes[1].value.as_str()
+            )
+        );
+        create_lunar(deps.as_mut(), Addr::unchecked("creator"), 20220101, lunar).unwrap();
+    }
+
+    #[test]
+    fn test_simplified_chinese() {
+        let mut deps = mock_dependencies();
+        // the dictionary is kept in traditional characters whatever script it is given in
+        setup(deps.as_mut(), "竖柱上梁 纳财");
+        let res = list_activities(deps.as_ref(), None, None).unwrap();
+        assert_eq!(
+            vec![(0, "豎柱上梁".to_string()), (1, "納財".to_string())],
+            res.activities
+        );
+
+        let lunar = lunar(20220101, "豎柱上梁", "纳财");
+        create_lunar(deps.as_mut(), Addr::unchecked("creator"), 20220101, lunar).unwrap();
+
+        for predicates in [
+            vec!["good_for=竖柱上梁", "bad_for=纳财"],
+            vec!["good_for=豎柱上梁", "bad_for=納財"],
+        ] {
+            let res = try_find_lunar(
+                deps.as_ref(),
+                2022,
+                predicates
+                    .into_iter()
+                    .map(|p| Predicate::and(vec![p]))
+                    .collect(),
+            );
+            assert_eq!(Some(vec![20220101]), res);
+        }
+    }
+
+    #[test]
+    fn test_activity_aliases() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut(), "嫁娶 開市 安葬");
+
+        let lunar = lunar(20220101, "嫁娶 開市", "安葬");
+        create_lunar(
+            deps.as_mut(),
+            Addr::unchecked("creator"),
+            20220101,
+            lunar.clone(),
+        )
+        .unwrap();
+
+        for value in ["嫁娶", "marriage", "Jia Qu", "jiaqu"] {
+            let res = try_find_lunar(
+                deps.as_ref(),
+                2022,
+                vec![Predicate::And(vec![("good_for".into(), value.into())])],
+            );
+            assert_eq!(Some(vec![20220101]), res, "{}", value);
+        }
+        let res = try_find_lunar(
+            deps.as_ref(),
+            2022,
+            vec![Predicate::and(vec!["bad_for=burial"])],
+        );
+        assert_eq!(Some(vec![20220101]), res);
+
+        let translation = translate_lunar(
+            &Lunar {
+                good_for: "嫁娶 開市 未知".into(),
+                ..lunar
+            },
+            Language::English,
+        );
+        assert_eq!(
+            vec!["marriage", "opening business", "未知"],
+            translation.good_for
+        );
+        assert_eq!(vec!["burial"], translation.bad_for);
+    }
+
+    #[test]
+    fn test_validate_lunar() {
+        let valid = lunar(20220131, "嫁娶 開市", "安葬");
+        validate_lunar(20220131, &valid).unwrap();
+
+        let cases = vec![
+            (20220230, valid.clone(), "20220230 is not a valid date"),
+            (20221331, valid.clone(), "20221331 is not a valid date"),
+            (
+                20220131,
+                Lunar {
+                    eight_words: " ".into(),
+                    ..valid.clone()
+                },
+                "Field eight_words must not be empty",
+            ),
+            (
+                20220130,
+                valid.clone(),
+                "Date \"2022-01-31\" does not match 20220130",
+            ),
+            (
+                220131,
+                valid.clone(),
+                "Date \"2022-01-31\" does not match 220131",
+            ),
+            (
+                20220131,
+                Lunar {
+                    lunar_number: (2021, 13, 1),
+                    ..valid.clone()
+                },
+                "Invalid lunar number (2021, 13, 1)",
+            ),
+            (
+                20220131,
+                Lunar {
+                    lunar_number: (2020, 12, 29),
+                    ..valid.clone()
+                },
+                "Invalid lunar number (2020, 12, 29)",
+            ),
+            (
+                20220131,
+                Lunar {
+                    bad_for: "安葬 开市".into(),
+                    ..valid.clone()
+                },
+                "Activity \"開市\" is both good and bad",
+            ),
+        ];
+        for (yyyymmdd, lunar, expected) in cases {
+            let err = validate_lunar(yyyymmdd, &lunar).unwrap_err();
+            assert_eq!(expected, err.to_string());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "zodiatic")]
+    fn test_find_lunar_missing_records() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut(), "嫁娶 安葬");
+
+        // the mock chain has no records, so every indexed day is missing from it
+        for yyyymmdd in [20220101, 20220102] {
+            let lunar = lunar(yyyymmdd, "嫁娶", "安葬");
+            create_lunar(deps.as_mut(), Addr::unchecked("creator"), yyyymmdd, lunar).unwrap();
+        }
+        let predicates = vec![Predicate::and(vec!["good_for=嫁娶"])];
+
+        let res = find_lunar(deps.as_ref(), 2022, predicates.clone(), false, None).unwrap();
+        assert_eq!(
+            vec![
+                LunarRow {
+                    yyyymmdd: 20220101,
+                    status: RowStatus::Missing,
+                    lunar: None,
+                    translation: None,
+                    matched: vec![("good_for".into(), "嫁娶".into())],
+                },
+                LunarRow {
+                    yyyymmdd: 20220102,
+                    status: RowStatus::Missing,
+                    lunar: None,
+                    translation: None,
+                    matched: vec![("good_for".into(), "嫁娶".into())],
+                },
+            ],
+            res.result
+        );
+
+        find_lunar(deps.as_ref(), 2022, predicates, true, None).unwrap_err();
+    }
+
+    #[test]
+    #[cfg(feature = "zodiatic")]
+    fn test_chain_query_failures() {
+        let days =
+            [20220101, 20220102, 20220103].map(|yyyymmdd| (yyyymmdd, lunar(yyyymmdd, "嫁娶", "")));
+        let app = ZodiaticAppBuilder::new().with_lunars(days.clone()).build();
+        let querier = MockZodiaticQuerier::new(app)
+            .with_failure(20220102, Failure::Missing)
+            .with_failure(20220103, Failure::Error("module unavailable".into()));
+        let mut deps = mock_dependencies_with_custom_querier(querier);
+        setup(deps.as_mut(), "嫁娶");
+        for (yyyymmdd, lunar) in days {
+            create_lunar(deps.as_mut(), Addr::unchecked("creator"), yyyymmdd, lunar).unwrap();
+        }
+
+        let res = get_lunar(deps.as_ref(), 20220101, None).unwrap();
+        assert_eq!(lunar(20220101, "嫁娶", ""), res.lunar);
+        let err = get_lunar(deps.as_ref(), 20220102, None).unwrap_err();
+        assert!(err.to_string().contains("Lunar 20220102 not found"));
+        let err = get_lunar(deps.as_ref(), 20220103, None).unwrap_err();
+        assert!(err.to_string().contains("module unavailable"));
+
+        // one failing day fails the whole batch, but the rows still tell an absent record
+        // from a chain that failed to answer
+        let predicates = vec![Predicate::and(vec!["good_for=嫁娶"])];
+        deps.querier.reset_counters();
+        let res = find_lunar(deps.as_ref(), 2022, predicates.clone(), false, None).unwrap();
+        assert_eq!(
+            vec![
+                (RowStatus::Found, true),
+                (RowStatus::Missing, false),
+                (RowStatus::Unavailable, false)
+            ],
+            res.result
+                .into_iter()
+                .map(|row| (row.status, row.lunar.is_some()))
+                .collect::<Vec<_>>()
+        );
+        // the batch, then each day on its own
+        assert_eq!(4, deps.querier.call_count());
+        let err = find_lunar(deps.as_ref(), 2022, predicates.clone(), true, None).unwrap_err();
+        assert!(err.to_string().contains("module unavailable"));
+
+        // partial data only marks the day the chain has no record of
+        deps.querier.clear_failures();
+        deps.querier.set_failure(20220102, Failure::Missing);
+        let english = Some(Language::English);
+        let res = find_lunar(deps.as_ref(), 2022, predicates.clone(), false, english).unwrap();
+        assert_eq!(
+            vec![
+                (RowStatus::Found, Some(vec!["marriage".to_string()])),
+                (RowStatus::Missing, None),
+                (RowStatus::Found, Some(vec!["marriage".to_string()]))
+            ],
+            res.result
+                .into_iter()
+                .map(|row| (row.status, row.translation.map(|t| t.good_for)))
+                .collect::<Vec<_>>()
+        );
+        let err = find_lunar(deps.as_ref(), 2022, predicates.clone(), true, None).unwrap_err();
+        assert!(err.to_string().contains("Lunar 20220102"));
+
+        // a batch that leaves a day out does not lose its row
+        deps.querier.set_failure(20220103, Failure::Dropped);
+        let res = find_lunar(deps.as_ref(), 2022, predicates.clone(), false, None).unwrap();
+        assert_eq!(
+            vec![RowStatus::Found, RowStatus::Missing, RowStatus::Unavailable],
+            res.result
+                .into_iter()
+                .map(|row| row.status)
+                .collect::<Vec<_>>()
+        );
+        let err = find_lunar(deps.as_ref(), 2022, predicates, true, None).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Expected 3 records in the batch, got 2"));
+        deps.querier.clear_failures();
+        deps.querier.set_failure(20220102, Failure::Missing);
+
+        // only a day the chain has no record of is written without a previous record
+        let creator = Addr::unchecked("creator");
+        update_lunar(
+            deps.as_mut(),
+            creator.clone(),
+            20220102,
+            lunar(20220102, "嫁娶", ""),
+        )
+        .unwrap();
+        deps.querier
+            .set_failure(20220103, Failure::Error("module unavailable".into()));
+        let err = update_lunar(
+            deps.as_mut(),
+            creator.clone(),
+            20220103,
+            lunar(20220103, "嫁娶", ""),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("module unavailable"));
+        let err = delete_lunar(deps.as_mut(), creator, 20220103).unwrap_err();
+        assert!(err.to_string().contains("module unavailable"));
+    }
+
+    #[test]
+    #[cfg(feature = "zodiatic")]
+    fn test_chain_query_fan_out() {
+        let keys: Vec<u64> = (20220101..=20220110).collect();
+        let days = keys
+            .iter()
+            .map(|yyyymmdd| (*yyyymmdd, lunar(*yyyymmdd, "嫁娶", "")));
+        let app = ZodiaticAppBuilder::new().with_lunars(days.clone()).build();
+        let querier = MockZodiaticQuerier::new(app)
+            .with_latency(Duration::from_millis(20), Duration::from_millis(1));
+        let mut deps = mock_dependencies_with_custom_querier(querier);
+        setup(deps.as_mut(), "嫁娶 開市");
+        for (yyyymmdd, lunar) in days {
+            create_lunar(deps.as_mut(), Addr::unchecked("creator"), yyyymmdd, lunar).unwrap();
+        }
+        // indexing a new day does not query the chain
+        assert_eq!(0, deps.querier.call_count());
+
+        // every matching day is fetched in a single query
+        let predicates = vec![Predicate::and(vec!["good_for=嫁娶"])];
+        let res = find_lunar(deps.as_ref(), 2022, predicates, true, None).unwrap();
+        assert_eq!(10, res.result.len());
+        assert_eq!(vec![ZodiaticQuery::lunar_batch(keys)], deps.querier.calls());
+        assert_eq!(Duration::from_millis(30), deps.querier.total_latency());
+
+        // an update reads the record it replaces, once
+        deps.querier.reset_counters();
+        let fixed = lunar(20220105, "開市", "");
+        update_lunar(deps.as_mut(), Addr::unchecked("creator"), 20220105, fixed).unwrap();
+        assert_eq!(
+            vec![ZodiaticQuery::lunar_batch(vec![20220105])],
+            deps.querier.calls()
+        );
+        assert_eq!(Duration::from_millis(21), deps.querier.total_latency());
+    }
+
+    #[test]
+    fn test_find_lunar_explains_matches() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut(), "嫁娶 開市 安葬 祭祀");
+
+        for (yyyymmdd, good_for) in [
+            (20220101, "嫁娶 開市"),
+            (20220102, "開市"),
+            (20220103, "祭祀"),
+        ] {
+            let lunar = lunar(yyyymmdd, good_for, "安葬");
+            create_lunar(deps.as_mut(), Addr::unchecked("creator"), yyyymmdd, lunar).unwrap();
+        }
+
+        let res = find_lunar(
+            deps.as_ref(),
+            2022,
+            vec![
+                Predicate::and(vec!["good_for=marriage", "good_for=開市"]),
+                Predicate::and(vec!["bad_for=安葬"]),
+                Predicate::and_not(vec!["good_for=祭祀"]),
+            ],
+            false,
+            None,
+        )
+        .unwrap();
+
+        // pairs are reported as requested, and only those the day satisfies
+        let matched = res
+            .result
+            .into_iter()
+            .map(|row| (row.yyyymmdd, row.matched))
+            .collect::<Vec<_>>();
+        let pair = |field: &str, value: &str| (field.to_string(), value.to_string());
+        assert_eq!(
+            vec![
+                (
+                    20220101,
+                    vec![
+                        pair("good_for", "marriage"),
+                        pair("good_for", "開市"),
+                        pair("bad_for", "安葬")
+                    ]
+                ),
+                (
+                    20220102,
+                    vec![pair("good_for", "開市"), pair("bad_for", "安葬")]
+                ),
+            ],
+            matched
+        );
+    }
+
+    #[test]
+    fn test_rank_lunar() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut(), "嫁娶 開市 安葬 祭祀 出行");
+
+        for (yyyymmdd, good_for, bad_for) in [
+            (20211231, "嫁娶 開市 出行", "安葬"),
+            (20220101, "嫁娶 開市", "安葬"),
+            (20220102, "嫁娶", "祭祀"),
+            (20220103, "開市 出行", "嫁娶"),
+            (20220104, "出行", "安葬"),
+        ] {
+            let lunar = lunar(yyyymmdd, good_for, bad_for);
+            create_lunar(deps.as_mut(), Addr::unchecked("creator"), yyyymmdd, lunar).unwrap();
+        }
+
+        let rank = |limit| {
+            rank_lunar(
+                deps.as_ref(),
+                20220101,
+                20220131,
+                vec![
+                    Preference::new("good_for=嫁娶", 3),
+                    Preference::new("good_for=開市", 2),
+                ],
+                vec![Preference::new("bad_for=祭祀", 4)],
+                limit,
+            )
+            .unwrap()
+            .result
+        };
+        let ranked = |yyyymmdd, score| RankedLunar { yyyymmdd, score };
+
+        assert_eq!(
+            vec![
+                ranked(20220101, 5),
+                ranked(20220103, 2),
+                ranked(20220104, 0),
+                ranked(20220102, -1),
+            ],
+            rank(None)
+        );
+        assert_eq!(
+            vec![ranked(20220101, 5), ranked(20220103, 2)],
+            rank(Some(2))
+        );
+
+        // the range spans years
+        let res = rank_lunar(
+            deps.as_ref(),
+            20211201,
+            20220102,
+            vec![Preference::new("good_for=出行", 1)],
+            vec![],
+            Some(1),
+        )
+        .unwrap();
+        assert_eq!(vec![ranked(20211231, 1)], res.result);
+    }
+
+    #[test]
+    fn test_next_and_prev_lunar() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut(), "開市 嫁娶");
+
+        // created out of order, and across three years
+        for (yyyymmdd, good_for) in [
+            (20220110, "開市"),
+            (20220105, "開市"),
+            (20211230, "開市"),
+            (20220120, "嫁娶"),
+            (20230101, "開市"),
+        ] {
+            let lunar = lunar(yyyymmdd, good_for, "");
+            create_lunar(deps.as_mut(), Addr::unchecked("creator"), yyyymmdd, lunar).unwrap();
+        }
+        let predicates = vec![Predicate::and(vec!["good_for=開市"])];
+
+        let next = |after, limit| {
+            next_lunar(deps.as_ref(), after, predicates.clone(), limit)
+                .unwrap()
+                .result
+        };
+        assert_eq!(vec![20220105], next(20220101, None));
+        assert_eq!(vec![20220110], next(20220105, None));
+        assert_eq!(vec![20220105, 20220110, 20230101], next(20211230, Some(5)));
+        assert_eq!(vec![20230101], next(20220110, Some(5)));
+        assert_eq!(Vec::<u64>::new(), next(20230101, None));
+
+        let prev = |before, limit| {
+            prev_lunar(deps.as_ref(), before, predicates.clone(), limit)
+                .unwrap()
+                .result
+        };
+        assert_eq!(vec![20220110], prev(20230101, None));
+        assert_eq!(vec![20220105, 20211230], prev(20220110, Some(2)));
+        assert_eq!(Vec::<u64>::new(), prev(20211230, None));
+    }
+
+    #[test]
+    fn test_local_date() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut(), "");
+        let state = STATE.load(&deps.storage).unwrap();
+        assert_eq!(480, state.utc_offset_minutes);
+
+        // 2022-01-31T16:30:00Z is already February 1st in UTC+8
+        let time = Timestamp::from_seconds(1643646600);
+        assert_eq!(20220201, local_yyyymmdd(time, state.utc_offset_minutes, 0));
+        assert_eq!(20220202, local_yyyymmdd(time, state.utc_offset_minutes, 1));
+        assert_eq!(20220131, local_yyyymmdd(time, 0, 0));
+        assert_eq!(20220131, local_yyyymmdd(time, -5 * 60, 0));
+        assert_eq!(
+            20000301,
+            local_yyyymmdd(Timestamp::from_seconds(951782400), 0, 1)
+        );
+        assert_eq!(19691231, local_yyyymmdd(Timestamp::from_seconds(0), -60, 0));
+
+        let err = set_utc_offset(deps.as_mut(), Addr::unchecked("creator"), 15 * 60).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidUtcOffset { .. }));
+        let err = set_utc_offset(deps.as_mut(), Addr::unchecked("anyone"), 0).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        set_utc_offset(deps.as_mut(), Addr::unchecked("creator"), -5 * 60).unwrap();
+        assert_eq!(-300, STATE.load(&deps.storage).unwrap().utc_offset_minutes);
+    }
+
+    #[test]
+    fn test_lunar_hours() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut(), "嫁娶 開市 安葬 出行");
+
+        let day = lunar(20220101, "嫁娶", "安葬").with_hours(vec![
+            LunarHour::new("子時", "馬", "出行", "嫁娶"),
+            LunarHour::new("丑时", "羊", "嫁娶 開市", "出行"),
+        ]);
+        create_lunar(deps.as_mut(), Addr::unchecked("creator"), 20220101, day).unwrap();
+        let day = lunar(20220102, "嫁娶", "安葬")
+            .with_hours(vec![LunarHour::new("子", "馬", "開市", "出行")]);
+        create_lunar(deps.as_mut(), Addr::unchecked("creator"), 20220102, day).unwrap();
+
+        let find =
+            |predicate| try_find_lunar(deps.as_ref(), 2022, vec![Predicate::and(vec![predicate])]);
+        assert_eq!(Some(vec![20220101, 20220102]), find("good_for=嫁娶"));
+        assert_eq!(Some(vec![20220101]), find("good_for@丑時=嫁娶"));
+        assert_eq!(Some(vec![20220101]), find("good_for@丑=marriage"));
+        // the branch as the almanac data sometimes misspells it
+        assert_eq!(Some(vec![20220101]), find("good_for@醜時=嫁娶"));
+        assert_eq!(Some(vec![20220102]), find("good_for@子時=開市"));
+        assert_eq!(Some(vec![20220102]), find("bad_for@子=出行"));
+        assert_eq!(Some(vec![20220101]), find("bad_for@子=嫁娶"));
+        assert_eq!(Some(vec![]), find("bad_for@午時=出行"));
+        assert_eq!(Some(vec![]), find("bad_for@正午=出行"));
+        assert_eq!(Some(vec![20220101, 20220102]), find("clash@子=馬"));
+        assert_eq!(Some(vec![20220101]), find("clash@丑時=羊"));
+
+        let day = lunar(20220103, "嫁娶", "安葬");
+        let err = validate_lunar(
+            20220103,
+            &day.clone()
+                .with_hours(vec![LunarHour::new("正午", "馬", "", "")]),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidHour { .. }));
+        let err = validate_lunar(
+            20220103,
+            &day.clone().with_hours(vec![
+                LunarHour::new("子時", "馬", "", ""),
+                LunarHour::new("子", "馬", "", ""),
+            ]),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::DuplicateHour { .. }));
+        let err = validate_lunar(
+            20220103,
+            &day.clone()
+                .with_hours(vec![LunarHour::new("子時", "馬", "開市", "開市")]),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::OverlappingActivity { .. }));
+        let err = validate_lunar(
+            20220103,
+            &day.with_hours(vec![LunarHour::new("子時", "午", "", "")]),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidAnimal { .. }));
+    }
+
+    #[test]
+    fn test_not_clashing_with() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut(), "嫁娶");
+
+        let days = [
+            (20220101, "虎", "南"),
+            (20220102, "兔", "東"),
+            (20220103, "龙", "北"),
+        ];
+        for (yyyymmdd, clash, sha) in days {
+            let lunar = lunar(yyyymmdd, "嫁娶", "").with_clash(clash, sha);
+            create_lunar(deps.as_mut(), Addr::unchecked("creator"), yyyymmdd, lunar).unwrap();
+        }
+
+        // 1986 was a year of the tiger, 1987 of the rabbit
+        let find = |predicates| try_find_lunar(deps.as_ref(), 2022, predicates);
+        assert_eq!(
+            Some(vec![20220102, 20220103]),
+            find(vec![Predicate::not_clashing_with(1986)])
+        );
+        assert_eq!(
+            Some(vec![20220101]),
+            find(vec![
+                Predicate::not_clashing_with(1987),
+                Predicate::and(vec!["sha=南", "sha=北"]),
+                Predicate::and_not(vec!["clash=龍"]),
+            ])
+        );
+
+        // the clash form of almanacs is indexed as the animal
+        let day = lunar(20220104, "嫁娶", "").with_clash("沖虎", "南");
+        create_lunar(deps.as_mut(), Addr::unchecked("creator"), 20220104, day).unwrap();
+        let find = |predicates| try_find_lunar(deps.as_ref(), 2022, predicates);
+        assert_eq!(
+            Some(vec![20220101, 20220104]),
+            find(vec![Predicate::and(vec!["clash=虎"])])
+        );
+        assert_eq!(
+            Some(vec![20220101, 20220104]),
+            find(vec![Predicate::and(vec!["clash=沖虎"])])
+        );
+        assert_eq!(
+            Some(vec![20220102, 20220103]),
+            find(vec![Predicate::not_clashing_with(1986)])
+        );
+
+        let day = lunar(20220105, "嫁娶", "").with_clash("沖午", "南");
+        let err = validate_lunar(20220105, &day).unwrap_err();
+        assert_eq!("Unknown zodiac animal \"沖午\"", err.to_string());
+    }
+
+    #[test]
+    fn test_solar_terms() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut(), "祭祀");
+
+        let terms = get_solar_terms(2022).unwrap().terms;
+        assert_eq!(24, terms.len());
+        assert_eq!(("清明".to_string(), 20220405), terms[6]);
+        let err = get_solar_terms(1900).unwrap_err();
+        assert_eq!(
+            "Generic error: Solar terms are only known from 1901 to 2100",
+            err.to_string()
+        );
+
+        let msg = QueryMsg::SolarTermOn { yyyymmdd: 20221222 };
+        let res: SolarTermOnResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(Some("冬至".to_string()), res.solar_term);
+
+        for yyyymmdd in [20220404, 20220405, 20220406] {
+            let lunar = lunar(yyyymmdd, "祭祀", "");
+            create_lunar(deps.as_mut(), Addr::unchecked("creator"), yyyymmdd, lunar).unwrap();
+        }
+        let find = |predicates| try_find_lunar(deps.as_ref(), 2022, predicates);
+        assert_eq!(
+            Some(vec![20220405]),
+            find(vec![Predicate::and(vec!["solar_term=清明"])])
+        );
+        assert_eq!(
+            Some(vec![20220404, 20220406]),
+            find(vec![Predicate::and_not(vec![
+                "solar_term=清明",
+                "solar_term=谷雨"
+            ])])
+        );
+    }
+
+    #[test]
+    fn test_festivals() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut(), "祭祀");
+
+        let days = [
+            (20220910, (2022, 8, 15), "二零二二 壬寅[虎]年 八月小十五"),
+            (20220131, (2021, 12, 29), "二零二一 辛醜[牛]年 臘月小廿九"),
+            (20220201, (2022, 1, 1), "二零二二 壬寅[虎]年 正月大初一"),
+            (20220202, (2022, 1, 2), "二零二二 壬寅[虎]年 正月大初二"),
+            (20220405, (2022, 3, 5), "二零二二 壬寅[虎]年 三月大初五"),
+        ];
+        for (yyyymmdd, lunar_number, text) in days {
+            let lunar = Lunar {
+                lunar_number,
+                lunar: text.to_string(),
+                ..lunar(yyyymmdd, "祭祀", "")
+            };
+            create_lunar(deps.as_mut(), Addr::unchecked("creator"), yyyymmdd, lunar).unwrap();
+        }
+
+        let msg = QueryMsg::ListFestivals { year: 2022 };
+        let res: ListFestivalsResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        // 冬至 falls on 20221222, which has no record, but is listed as it is a solar term
+        let expected = [
+            ("除夕", 20220131),
+            ("春節", 20220201),
+            ("清明", 20220405),
+            ("中秋", 20220910),
+            ("冬至", 20221222),
+        ];
+        assert_eq!(
+            expected
+                .iter()
+                .map(|(name, yyyymmdd)| (name.to_string(), *yyyymmdd))
+                .collect::<Vec<_>>(),
+            res.festivals
+        );
+        // a year without records only has the solar-term festivals, and only while solar
+        // terms are known
+        assert_eq!(
+            vec![
+                ("清明".to_string(), 20230405),
+                ("冬至".to_string(), 20231222)
+            ],
+            list_festivals(deps.as_ref(), 2023).unwrap().festivals
+        );
+        assert!(list_festivals(deps.as_ref(), 2101)
+            .unwrap()
+            .festivals
+            .is_empty());
+
+        let find = |predicates| try_find_lunar(deps.as_ref(), 2022, predicates);
+        assert_eq!(
+            Some(vec![20220201]),
+            find(vec![Predicate::and(vec!["festival=春节"])])
+        );
+        assert_eq!(
+            Some(vec![20220202]),
+            find(vec![Predicate::and_not(vec![
+                "festival=除夕",
+                "festival=春節",
+                "festival=清明",
+                "festival=中秋"
+            ])])
+        );
+    }
+
+    #[test]
+    fn test_update_and_delete_lunar() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut(), "嫁娶 開市 安葬");
+
+        for yyyymmdd in [20220101, 20220102, 20220103] {
+            let lunar = lunar(yyyymmdd, "嫁娶", "安葬");
+            create_lunar(deps.as_mut(), Addr::unchecked("anyone"), yyyymmdd, lunar).unwrap();
+        }
+        let err = create_lunar(
+            deps.as_mut(),
+            Addr::unchecked("anyone"),
+            20220102,
+            lunar(20220102, "嫁娶", ""),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::LunarExists { yyyymmdd: 20220102 }
+        ));
+
+        let fixed = lunar(20220102, "開市", "嫁娶");
+        let err = update_lunar(
+            deps.as_mut(),
+            Addr::unchecked("anyone"),
+            20220102,
+            fixed.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let res = update_lunar(
+            deps.as_mut(),
+            Addr::unchecked("creator"),
+            20220102,
+            fixed.clone(),
+        )
+        .unwrap();
+        #[cfg(feature = "zodiatic")]
+        assert_eq!(
+            CosmosMsg::Custom(ZodiaticMsg::update_lunar("creator".into(), 20220102, fixed)),
+            res.messages[0].msg
+        );
+        #[cfg(not(feature = "zodiatic"))]
+        assert!(res.messages.is_empty());
+
+        let find = |deps: Deps<ChainQuery>, predicates| try_find_lunar(deps, 2022, predicates);
+        assert_eq!(
+            Some(vec![20220101, 20220103]),
+            find(deps.as_ref(), vec![Predicate::and(vec!["good_for=嫁娶"])])
+        );
+        assert_eq!(
+            Some(vec![20220102]),
+            find(
+                deps.as_ref(),
+                vec![Predicate::and(vec!["bad_for=嫁娶", "good_for=開市"])]
+            )
+        );
+
+        delete_lunar(deps.as_mut(), Addr::unchecked("creator"), 20220101).unwrap();
+        let err = delete_lunar(deps.as_mut(), Addr::unchecked("creator"), 20220101).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::LunarNotFound { yyyymmdd: 20220101 }
+        ));
+        let err = update_lunar(
+            deps.as_mut(),
+            Addr::unchecked("creator"),
+            20220104,
+            lunar(20220104, "嫁娶", ""),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::LunarNotFound { yyyymmdd: 20220104 }
+        ));
+
+        // deleted days match neither positive nor negative predicates
+        assert_eq!(
+            Some(vec![20220103]),
+            find(deps.as_ref(), vec![Predicate::and(vec!["good_for=嫁娶"])])
+        );
+        assert_eq!(
+            Some(vec![20220102, 20220103]),
+            find(
+                deps.as_ref(),
+                vec![Predicate::and_not(vec!["good_for=祭祀"])]
+            )
+        );
+
+        // a deleted day can be created again, in a new row
+        let lunar = lunar(20220101, "開市", "");
+        create_lunar(deps.as_mut(), Addr::unchecked("anyone"), 20220101, lunar).unwrap();
+        assert_eq!(
+            Some(vec![20220102, 20220101]),
+            find(deps.as_ref(), vec![Predicate::and(vec!["good_for=開市"])])
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "zodiatic"))]
+    fn test_records_in_contract_storage() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut(), "嫁娶 開市");
+
+        for yyyymmdd in [20220101, 20220102] {
+            let lunar = lunar(yyyymmdd, "嫁娶", "");
+            create_lunar(deps.as_mut(), Addr::unchecked("anyone"), yyyymmdd, lunar).unwrap();
+        }
+        let fixed = lunar(20220102, "開市", "");
+        update_lunar(
+            deps.as_mut(),
+            Addr::unchecked("creator"),
+            20220102,
+            fixed.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            fixed,
+            get_lunar(deps.as_ref(), 20220102, None).unwrap().lunar
+        );
+
+        let predicates = vec![Predicate::and(vec!["good_for=嫁娶"])];
+        let res = find_lunar(deps.as_ref(), 2022, predicates.clone(), true, None).unwrap();
+        assert_eq!(
+            vec![20220101],
+            res.result
+                .iter()
+                .map(|row| row.yyyymmdd)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(RowStatus::Found, res.result[0].status);
+
+        delete_lunar(deps.as_mut(), Addr::unchecked("creator"), 20220101).unwrap();
+        get_lunar(deps.as_ref(), 20220101, None).unwrap_err();
+
+        // records handed over by governance are kept too, and the index can be rebuilt from them
+        ingest_lunar(deps.as_mut(), 20220103, lunar(20220103, "嫁娶", "")).unwrap();
+        let res = rebuild_index(deps.as_mut(), 2022).unwrap();
+        assert_eq!(
+            ("indexed", "2"),
+            (
+                res.attributes[2].key.as_str(),
+                res.attributes[2].value.as_str()
+            )
+        );
+        let res = find_lunar(deps.as_ref(), 2022, predicates, true, None).unwrap();
+        assert_eq!(
+            vec![20220103],
+            res.result
+                .iter()
+                .map(|row| row.yyyymmdd)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "zodiatic")]
+    fn test_fixes_reach_chain() {
+        let (mut app, contract) = setup_app("嫁娶 開市");
+        let creator = Addr::unchecked("creator");
+
+        let msgs = [
+            ExecuteMsg::CreateLunar {
+                yyyymmdd: 20220101,
+                lunar: lunar(20220101, "嫁娶", ""),
+            },
+            ExecuteMsg::UpdateLunar {
+                yyyymmdd: 20220101,
+                lunar: lunar(20220101, "開市", "嫁娶"),
+            },
+        ];
+        for msg in msgs {
+            app.execute_contract(creator.clone(), contract.clone(), &msg, &[])
+                .unwrap();
+        }
+        let sent = app.zodiatic_messages();
+        assert_eq!(
+            vec![
+                ZodiaticMsg::create_lunar("creator".into(), 20220101, lunar(20220101, "嫁娶", "")),
+                ZodiaticMsg::update_lunar(
+                    "creator".into(),
+                    20220101,
+                    lunar(20220101, "開市", "嫁娶")
+                ),
+            ],
+            sent.iter().map(|sent| sent.msg.clone()).collect::<Vec<_>>()
+        );
+        assert!(sent.iter().all(|sent| sent.sender == contract));
+        assert_eq!(app.block_info().height, sent[0].height);
+        app.clear_zodiatic_messages();
+
+        let get_lunar = QueryMsg::GetLunar {
+            yyyymmdd: 20220101,
+            lang: None,
+        };
+        let res: GetLunarResponse = app
+            .wrap()
+            .query_wasm_smart(contract.clone(), &get_lunar)
+            .unwrap();
+        assert_eq!(lunar(20220101, "開市", "嫁娶"), res.lunar);
+
+        let msg = ExecuteMsg::DeleteLunar { yyyymmdd: 20220101 };
+        app.execute_contract(creator, contract.clone(), &msg, &[])
+            .unwrap();
+        let sent = app.zodiatic_messages();
+        assert_eq!(
+            vec![ZodiaticMsg::delete_lunar("creator".into(), 20220101)],
+            sent.into_iter().map(|sent| sent.msg).collect::<Vec<_>>()
+        );
+        let err = app
+            .wrap()
+            .query_wasm_smart::<GetLunarResponse>(contract, &get_lunar)
+            .unwrap_err();
+        assert!(err.to_string().contains("Lunar 20220101 not found"));
+    }
+
+    #[test]
+    #[cfg(feature = "zodiatic")]
+    fn test_bulk_queries() {
+        let (mut app, contract) = setup_app("嫁娶 開市");
+        for (yyyymmdd, good_for) in [(20220101, "嫁娶"), (20220102, "開市"), (20220103, "嫁娶")]
+        {
+            let msg = ExecuteMsg::CreateLunar {
+                yyyymmdd,
+                lunar: lunar(yyyymmdd, good_for, ""),
+            };
+            app.execute_contract(Addr::unchecked("anyone"), contract.clone(), &msg, &[])
+                .unwrap();
+        }
+
+        let wrapper = app.wrap();
+        let querier = ZodiaticQuerier::new(&wrapper);
+        let res = querier.lunar_range(20220102, 20220131).unwrap();
+        assert_eq!(
+            vec![20220102, 20220103],
+            res.lunars.iter().map(|(key, _)| *key).collect::<Vec<_>>()
+        );
+        let res = querier.lunar_batch(vec![20220103, 20220104]).unwrap();
+        assert_eq!(vec![Some(lunar(20220103, "嫁娶", "")), None], res.lunars);
+        assert!(querier.lunar_exists(20220101).unwrap().exists);
+        assert!(!querier.lunar_exists(20220104).unwrap().exists);
+
+        let msg = QueryMsg::FindLunar {
+            year: 2022,
+            predicates: vec![Predicate::and(vec!["good_for=嫁娶"])],
+            strict: Some(true),
+            lang: None,
+        };
+        let res: FindLunarResponse = app.wrap().query_wasm_smart(contract, &msg).unwrap();
+        assert_eq!(
+            vec![(20220101, RowStatus::Found), (20220103, RowStatus::Found)],
+            res.result
+                .into_iter()
+                .map(|row| (row.yyyymmdd, row.status))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "zodiatic")]
+    fn test_chain_params() {
+        let mut app = ZodiaticApp::new();
+        let params = ZodiaticQuerier::new(&app.wrap()).params().unwrap().params;
+        assert_eq!(ZodiaticModule::default_params(), params);
+
+        let params = Params {
+            first_year: 2000,
+            last_year: 2030,
+            data_source: "通勝 2022".to_string(),
+            permissioned_writes: true,
+        };
+        app.set_params(&params).unwrap();
+        let res = ZodiaticQuerier::new(&app.wrap()).params().unwrap();
+        assert_eq!(params, res.params);
+    }
+
+    #[test]
+    #[cfg(feature = "zodiatic")]
+    fn test_reply_undoes_rejected_changes() {
+        let mut deps = mock_dependencies();
+        setup(deps.as_mut(), "嫁娶 開市");
+        let failed = |deps: DepsMut<ChainQuery>| {
+            let msg = Reply {
+                id: chain::SYNC_REPLY_ID,
+                result: SubMsgResult::Err("rejected".into()),
+            };
+            let res = reply(deps, mock_env(), msg).unwrap();
+            assert_eq!("reverted", res.attributes[2].value);
+        };
+
+        for yyyymmdd in [20220101, 20220102] {
+            let lunar = lunar(yyyymmdd, "嫁娶", "");
+            let res =
+                create_lunar(deps.as_mut(), Addr::unchecked("creator"), yyyymmdd, lunar).unwrap();
+            assert_eq!(ReplyOn::Error, res.messages[0].reply_on);
+        }
+        let msg = Reply {
+            id: chain::SYNC_REPLY_ID,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        let err = reply(deps.as_mut(), mock_env(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::UnexpectedReply { id } if id == chain::SYNC_REPLY_ID));
+
+        let day = lunar(20220103, "開市", "");
+        create_lunar(deps.as_mut(), Addr::unchecked("creator"), 20220103, day).unwrap();
+        failed(deps.as_mut());
+        let find = |deps: Deps<ChainQuery>, predicates| try_find_lunar(deps, 2022, predicates);
+        assert_eq!(
+            Some(vec![]),
+            find(deps.as_ref(), vec![Predicate::and(vec!["good_for=開市"])])
+        );
+        assert_eq!(Some(vec![20220101, 20220102]), find(deps.as_ref(), vec![]));
+
+        // the mock chain has no record of 20220101, so there is nothing to restore it to
+        let day = lunar(20220101, "開市", "");
+        update_lunar(deps.as_mut(), Addr::unchecked("creator"), 20220101, day).unwrap();
+        failed(deps.as_mut());
+        assert_eq!(Some(vec![20220102]), find(deps.as_ref(), vec![]));
+
+        let err = reply(
+            deps.as_mut(),
+            mock_env(),
+            Reply {
+                id: 7,
+                result: SubMsgResult::Err("rejected".into()),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::UnknownReplyId { id: 7 }));
+    }
+
+    #[test]
+    #[cfg(feature = "zodiatic")]
+    fn test_rejected_writes_leave_index_consistent() {
+        let (mut app, contract) = setup_app("嫁娶 開市");
+        let creator = Addr::unchecked("creator");
+
+        // the chain already has 20220101, so the native module rejects creating it again
+        let msg = ZodiaticMsg::create_lunar("chain".into(), 20220101, lunar(20220101, "開市", ""));
+        app.execute(Addr::unchecked("chain"), msg.into()).unwrap();
+        let msg = ExecuteMsg::CreateLunar {
+            yyyymmdd: 20220101,
+            lunar: lunar(20220101, "嫁娶", ""),
+        };
+        let res = app
+            .execute_contract(creator.clone(), contract.clone(), &msg, &[])
+            .unwrap();
+        assert!(res.has_event(&Event::new("wasm").add_attribute("status", "reverted")));
+        // the rejected message is rolled back with its transaction
+        let sent = app.zodiatic_messages();
+        assert_eq!(
+            vec![Addr::unchecked("chain")],
+            sent.into_iter().map(|sent| sent.sender).collect::<Vec<_>>()
+        );
+
+        let find = QueryMsg::FindLunar {
+            year: 2022,
+            predicates: vec![],
+            strict: Some(true),
+            lang: None,
+        };
+        // strict, so a row pointing at a day missing on chain would fail the query
+        let res: FindLunarResponse = app
+            .wrap()
+            .query_wasm_smart(contract.clone(), &find)
+            .unwrap();
+        assert!(res.result.is_empty());
+
+        let msg = ExecuteMsg::CreateLunar {
+            yyyymmdd: 20220102,
+            lunar: lunar(20220102, "嫁娶", ""),
+        };
+        let res = app
+            .execute_contract(creator, contract.clone(), &msg, &[])
+            .unwrap();
+        // an accepted change gets no reply
+        assert!(!res.events.iter().any(|event| event.ty == "reply"));
+        let res: FindLunarResponse = app.wrap().query_wasm_smart(contract, &find).unwrap();
+        assert_eq!(
+            vec![20220102],
+            res.result
+                .iter()
+                .map(|row| row.yyyymmdd)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "zodiatic")]
+    fn test_today_on_seeded_chain() {
+        // 2022-01-31T16:30:00Z is already February 1st in UTC+8
+        let builder = ZodiaticAppBuilder::new()
+            .with_lunars(vec![
+                (20220131, lunar(20220131, "嫁娶", "")),
+                (20220201, lunar(20220201, "開市", "")),
+            ])
+            .with_start_time(Timestamp::from_seconds(1643646600));
+        let (mut app, contract) = setup_app_with(builder, "嫁娶 開市");
+
+        let res: TodayResponse = app
+            .wrap()
+            .query_wasm_smart(&contract, &QueryMsg::Today { lang: None })
+            .unwrap();
+        assert_eq!(20220201, res.yyyymmdd);
+        assert_eq!("開市", res.lunar.good_for);
+        assert_eq!(None, res.translation);
+        let msg = QueryMsg::Tomorrow {
+            lang: Some(Language::English),
+        };
+        app.wrap()
+            .query_wasm_smart::<TodayResponse>(&contract, &msg)
+            .unwrap_err();
+        let msg = QueryMsg::Today {
+            lang: Some(Language::English),
+        };
+        let res: TodayResponse = app.wrap().query_wasm_smart(&contract, &msg).unwrap();
+        assert_eq!(vec!["opening business"], res.translation.unwrap().good_for);
+
+        // the chain has no record of February 2nd
+        app.advance_seconds(24 * 60 * 60);
+        app.wrap()
+            .query_wasm_smart::<TodayResponse>(&contract, &QueryMsg::Today { lang: None })
+            .unwrap_err();
+    }
+
+    #[test]
+    #[cfg(feature = "zodiatic")]
+    fn test_sudo_ingestion() {
+        let (mut app, contract) = setup_app("嫁娶 開市");
+
+        // governance seeds the chain, and the index is rebuilt from it without an owner key
+        let lunars = vec![
+            (20220101, lunar(20220101, "嫁娶", "")),
+            (20220102, lunar(20220102, "開市", "")),
+        ];
+        app.zodiatic_sudo(ZodiaticSudo::SetLunars { lunars })
+            .unwrap();
+        app.wasm_sudo(contract.clone(), &SudoMsg::RebuildIndex { year: 2022 })
+            .unwrap();
+
+        let find = |app: &ZodiaticApp, good_for: &str| -> Vec<u64> {
+            let msg = QueryMsg::FindLunar {
+                year: 2022,
+                predicates: vec![Predicate::and(vec![good_for])],
+                strict: Some(true),
+                lang: None,
+            };
+            let res: FindLunarResponse = app.wrap().query_wasm_smart(&contract, &msg).unwrap();
+            res.result.iter().map(|row| row.yyyymmdd).collect()
+        };
+        assert_eq!(vec![20220101], find(&app, "good_for=嫁娶"));
+        assert_eq!(vec![20220102], find(&app, "good_for=開市"));
+
+        // the chain stores a new day and a fix of an existing one, and pushes both
+        for (yyyymmdd, good_for) in [(20220103, "嫁娶"), (20220101, "開市")] {
+            let lunar = lunar(yyyymmdd, good_for, "");
+            let lunars = vec![(yyyymmdd, lunar.clone())];
+            app.zodiatic_sudo(ZodiaticSudo::SetLunars { lunars })
+                .unwrap();
+            let msg = SudoMsg::IngestLunar { yyyymmdd, lunar };
+            app.wasm_sudo(contract.clone(), &msg).unwrap();
+        }
+        assert_eq!(vec![20220103], find(&app, "good_for=嫁娶"));
+        assert_eq!(vec![20220101, 20220102], find(&app, "good_for=開市"));
+
+        // a record with an activity the dictionary lacks needs it added first, by governance too
+        let day = lunar(20220104, "祭祀", "");
+        let lunars = vec![(20220104, day.clone())];
+        app.zodiatic_sudo(ZodiaticSudo::SetLunars { lunars })
+            .unwrap();
+        let msg = SudoMsg::IngestLunar {
+            yyyymmdd: 20220104,
+            lunar: day,
+        };
+        app.wasm_sudo(contract.clone(), &msg).unwrap_err();
+        let add = SudoMsg::AddActivities {
+            activities: vec!["祭祀".into()],
+        };
+        app.wasm_sudo(contract.clone(), &add).unwrap();
+        app.wasm_sudo(contract.clone(), &msg).unwrap();
+        assert_eq!(vec![20220104], find(&app, "good_for=祭祀"));
+
+        // a fix that was not pushed only shows up after a rebuild
+        let lunars = vec![(20220102, lunar(20220102, "嫁娶", ""))];
+        app.zodiatic_sudo(ZodiaticSudo::SetLunars { lunars })
+            .unwrap();
+        assert_eq!(vec![20220103], find(&app, "good_for=嫁娶"));
+        app.wasm_sudo(contract.clone(), &SudoMsg::RebuildIndex { year: 2022 })
+            .unwrap();
+        assert_eq!(vec![20220102, 20220103], find(&app, "good_for=嫁娶"));
+        assert_eq!(vec![20220101], find(&app, "good_for=開市"));
+    }
+
+    #[test]
+    #[cfg(feature = "zodiatic")]
+    fn test_rename_then_rebuild() {
+        let (mut app, contract) = setup_app("嫁娶 開市");
+        let creator = Addr::unchecked("creator");
+        let day = lunar(20220101, "開市", "");
+        let lunars = vec![(20220101, day.clone())];
+        app.zodiatic_sudo(ZodiaticSudo::SetLunars { lunars })
+            .unwrap();
+
+        let msg = ExecuteMsg::RenameActivity {
+            id: 1,
+            name: "開張".into(),
+        };
+        app.execute_contract(creator.clone(), contract.clone(), &msg, &[])
+            .unwrap();
+
+        // the record on chain still says 開市
+        app.wasm_sudo(contract.clone(), &SudoMsg::RebuildIndex { year: 2022 })
+            .unwrap();
+        let msg = SudoMsg::IngestLunar {
+            yyyymmdd: 20220101,
+            lunar: day.clone(),
+        };
+        app.wasm_sudo(contract.clone(), &msg).unwrap();
+        let msg = ExecuteMsg::UpdateLunar {
+            yyyymmdd: 20220101,
+            lunar: day,
+        };
+        app.execute_contract(creator, contract.clone(), &msg, &[])
+            .unwrap();
+
+        for good_for in ["good_for=開張", "good_for=開市"] {
+            let msg = QueryMsg::FindLunar {
+                year: 2022,
+                predicates: vec![Predicate::and(vec![good_for])],
+                strict: Some(true),
+                lang: None,
+            };
+            let res: FindLunarResponse = app.wrap().query_wasm_smart(&contract, &msg).unwrap();
+            assert_eq!(1, res.result.len());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "zodiatic")]
+    fn test_rebuild_skips_invalid_records() {
+        let (mut app, contract) = setup_app("嫁娶 開市");
+
+        // the chain has a record with a double-hour that the contract does not know
+        let bad = lunar(20220102, "開市", "").with_hours(vec![LunarHour::new("正午", "", "", "")]);
+        let lunars = vec![
+            (20220101, lunar(20220101, "嫁娶", "")),
+            (20220102, bad),
+            (20220103, lunar(20220103, "開市", "")),
+        ];
+        app.zodiatic_sudo(ZodiaticSudo::SetLunars { lunars })
+            .unwrap();
+        let res = app
+            .wasm_sudo(contract.clone(), &SudoMsg::RebuildIndex { year: 2022 })
+            .unwrap();
+        assert!(res.has_event(
+            &Event::new("wasm")
+                .add_attribute("indexed", "2")
+                .add_attribute("skipped", "20220102")
+        ));
+
+        let msg = QueryMsg::FindLunar {
+            year: 2022,
+            predicates: vec![],
+            strict: Some(true),
+            lang: None,
+        };
+        let res: FindLunarResponse = app.wrap().query_wasm_smart(&contract, &msg).unwrap();
+        assert_eq!(
+            vec![20220101, 20220103],
+            res.result
+                .iter()
+                .map(|row| row.yyyymmdd)
+                .collect::<Vec<_>>()
+        );
     }
 }
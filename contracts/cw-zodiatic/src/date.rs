@@ -0,0 +1,59 @@
+use cosmwasm_std::Timestamp;
+
+/// Splits `yyyymmdd` into year, month and day if it is a valid Gregorian date
+pub fn parse_yyyymmdd(yyyymmdd: u64) -> Option<(u32, u32, u32)> {
+    let year = u32::try_from(yyyymmdd / 10000).ok()?;
+    let month = (yyyymmdd / 100 % 100) as u32;
+    let day = (yyyymmdd % 100) as u32;
+
+    if !(1..=9999).contains(&year) || !(1..=12).contains(&month) {
+        return None;
+    }
+    if day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some((year, month, day))
+}
+
+/// Returns the local date `days_ahead` days after the given time, at `utc_offset_minutes`
+/// from UTC
+pub fn local_yyyymmdd(time: Timestamp, utc_offset_minutes: i32, days_ahead: i64) -> u64 {
+    let seconds = time.seconds() as i64 + i64::from(utc_offset_minutes) * 60;
+    let (year, month, day) = civil_from_days(seconds.div_euclid(86400) + days_ahead);
+    year as u64 * 10000 + u64::from(month) * 100 + u64::from(day)
+}
+
+/// Converts days since 1970-01-01 to a Gregorian date, see
+/// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Formats a date as `Lunar::date` does, e.g. "2022-01-31"
+pub fn format_date(year: u32, month: u32, day: u32) -> String {
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// `u32::is_multiple_of` is newer than the toolchain that contracts are built with
+#[allow(unknown_lints, clippy::manual_is_multiple_of)]
+pub fn is_leap_year(year: u32) -> bool {
+    year % 4 == 0 && year % 100 != 0 || year % 400 == 0
+}
+
+pub fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
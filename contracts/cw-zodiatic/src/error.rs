@@ -9,6 +9,66 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("{yyyymmdd} is not a valid date")]
+    InvalidDate { yyyymmdd: u64 },
+
+    #[error("Date {date:?} does not match {yyyymmdd}")]
+    DateMismatch { yyyymmdd: u64, date: String },
+
+    #[error("Field {field} must not be empty")]
+    EmptyField { field: String },
+
+    #[error("Invalid lunar number {lunar_number:?}")]
+    InvalidLunarNumber { lunar_number: (u64, u64, u64) },
+
+    #[error("Activity {activity:?} is both good and bad")]
+    OverlappingActivity { activity: String },
+
+    #[error("Lunar {yyyymmdd} already exists")]
+    LunarExists { yyyymmdd: u64 },
+
+    #[error("Lunar {yyyymmdd} not found")]
+    LunarNotFound { yyyymmdd: u64 },
+
+    #[error("Unknown zodiac animal {animal:?}")]
+    InvalidAnimal { animal: String },
+
+    #[error("Unknown double-hour {hour:?}")]
+    InvalidHour { hour: String },
+
+    #[error("Double-hour {hour} is listed more than once")]
+    DuplicateHour { hour: String },
+
+    #[error("Invalid UTC offset of {utc_offset_minutes} minutes")]
+    InvalidUtcOffset { utc_offset_minutes: i32 },
+
+    #[error("Activity {activity:?} already exists")]
+    ActivityExists { activity: String },
+
+    #[error("Unknown activity {activity:?} in {field}")]
+    UnknownActivity { field: String, activity: String },
+
+    #[error("Unknown activity id {id}")]
+    UnknownActivityId { id: u16 },
+
+    #[error("Activity name must not be empty")]
+    EmptyActivity {},
+
+    #[error("Activity {activity:?} must not contain whitespace")]
+    ActivityWithWhitespace { activity: String },
+
+    #[error("Activity {activity:?} must not be a number, which would read as an id")]
+    NumericActivity { activity: String },
+
+    #[error("Activity dictionary is full")]
+    ActivityLimit {},
+
+    #[error("Unknown reply id {id}")]
+    UnknownReplyId { id: u64 },
+
+    #[error("Unexpected reply {id} to a successful message")]
+    UnexpectedReply { id: u64 },
+
     #[error("Custom Error: {msg:?}")]
     CustomError { msg: String },
     // Add any other custom errors you like here.
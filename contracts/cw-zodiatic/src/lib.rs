@@ -1,5 +1,7 @@
-mod bitmap;
+pub mod bitmap;
+pub mod chain;
 pub mod contract;
+mod date;
 mod error;
 pub mod msg;
 pub mod state;
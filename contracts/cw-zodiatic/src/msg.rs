@@ -1,14 +1,52 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use zodiatic_bindings::Lunar;
+use zodiatic_bindings::{Language, Lunar};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    /// Offset from UTC of the local time used by `Today` and `Tomorrow`, UTC+8 by default
+    pub utc_offset_minutes: Option<i32>,
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
+    /// Indexes `lunar` and forwards it to the chain. Every activity in `good_for` and
+    /// `bad_for` must already be in the dictionary.
     CreateLunar { yyyymmdd: u64, lunar: Lunar },
+    /// Reindexes an existing day with `lunar` and forwards it to the chain. Only the owner
+    /// may do this.
+    UpdateLunar { yyyymmdd: u64, lunar: Lunar },
+    /// Removes a day from the index and from the chain. Only the owner may do this.
+    DeleteLunar { yyyymmdd: u64 },
+    /// Sets the offset from UTC used by `Today` and `Tomorrow`. Only the owner may do this.
+    SetUtcOffset { utc_offset_minutes: i32 },
+    /// Adds activities to the dictionary, skipping known ones. Only the owner may do this.
+    /// A name must not be empty, contain whitespace or be a number.
+    AddActivities { activities: Vec<String> },
+    /// Renames an activity in the dictionary. Only the owner may do this.
+    ///
+    /// The old name stays in the dictionary as another name of the same activity, since the
+    /// records on chain still use it. English and pinyin aliases are fixed to the built-in
+    /// names, so records written with the new name are not translated.
+    RenameActivity { id: u16, name: String },
+}
+
+/// Messages that only the chain can send, from the native Zodiatic module or governance
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum SudoMsg {
+    /// Indexes a record that is already on chain, replacing the day's index if it has one.
+    /// Every activity must already be in the dictionary, see `AddActivities`.
+    IngestLunar { yyyymmdd: u64, lunar: Lunar },
+    /// Adds activities to the dictionary, skipping known ones, as `ExecuteMsg::AddActivities`
+    /// does for the owner
+    AddActivities { activities: Vec<String> },
+    /// Rebuilds the index of `year` from the records on chain. Records that fail validation
+    /// are left out and listed in the `skipped` attribute.
+    RebuildIndex { year: u32 },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -16,11 +54,64 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     GetLunar {
         yyyymmdd: u64,
+        /// Also return the activities translated into this language
+        lang: Option<Language>,
     },
     FindLunar {
         year: u32,
         predicates: Vec<Predicate>,
+        /// Fail instead of reporting a `Missing` or `Unavailable` row when a matched day has no
+        /// record on chain or the chain fails to answer
+        strict: Option<bool>,
+        /// Also return the activities of each record translated into this language
+        lang: Option<Language>,
+    },
+    /// Returns the record of the local date at the current block time
+    Today {
+        /// Also return the activities translated into this language
+        lang: Option<Language>,
     },
+    /// Returns the record of the day after the local date at the current block time
+    Tomorrow {
+        /// Also return the activities translated into this language
+        lang: Option<Language>,
+    },
+    /// Returns the first `limit` (default 1) days after `after` that satisfy `predicates`,
+    /// earliest first, looking across years
+    NextLunar {
+        after: u64,
+        predicates: Vec<Predicate>,
+        limit: Option<u32>,
+    },
+    /// Returns the last `limit` (default 1) days before `before` that satisfy `predicates`,
+    /// latest first, looking across years
+    PrevLunar {
+        before: u64,
+        predicates: Vec<Predicate>,
+        limit: Option<u32>,
+    },
+    /// Scores every indexed day from `start` to `end` (inclusive) by adding the weights of
+    /// the wanted pairs it has and subtracting those of the unwanted ones, and returns the
+    /// best `limit` days, best first.
+    RankLunar {
+        start: u64,
+        end: u64,
+        wanted: Vec<Preference>,
+        unwanted: Vec<Preference>,
+        limit: Option<u32>,
+    },
+    ListActivities {
+        start_after: Option<u16>,
+        limit: Option<u32>,
+    },
+    /// Returns the dates of the 24 solar terms of `year`, which must be from 1901 to 2100
+    GetSolarTerms { year: u32 },
+    /// Returns the solar term that starts on `yyyymmdd`, if any
+    SolarTermOn { yyyymmdd: u64 },
+    /// Returns the festivals of `year` (a solar year), in date order. Festivals on a solar
+    /// term are always listed from 1901 to 2100. The lunar date of a day is only known from
+    /// its record, so other festivals are only listed for days that have one.
+    ListFestivals { year: u32 },
 }
 
 // We define a custom struct for each query response
@@ -28,18 +119,107 @@ pub enum QueryMsg {
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct GetLunarResponse {
     pub lunar: Lunar,
+    pub translation: Option<LunarTranslation>,
+}
+
+/// Activities of a `Lunar`, translated one by one. Activities without a translation are
+/// kept as they are, as are activities written under a name given by `RenameActivity`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LunarTranslation {
+    pub good_for: Vec<String>,
+    pub bad_for: Vec<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct FindLunarResponse {
-    pub result: Vec<Lunar>,
+    pub result: Vec<LunarRow>,
+}
+
+/// A day that matched the index, with its record on chain if there is one
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LunarRow {
+    pub yyyymmdd: u64,
+    pub status: RowStatus,
+    pub lunar: Option<Lunar>,
+    /// The activities of `lunar`, if a language was requested and there is a record
+    pub translation: Option<LunarTranslation>,
+    /// The `(field, value)` pairs of `And` predicates that this day satisfies, as requested
+    pub matched: Vec<(String, String)>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RowStatus {
+    Found,
+    /// The index refers to a day that the chain has no record of
+    Missing,
+    /// The chain failed to answer for this day
+    Unavailable,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TodayResponse {
+    pub yyyymmdd: u64,
+    pub lunar: Lunar,
+    pub translation: Option<LunarTranslation>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LunarDaysResponse {
+    pub result: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RankLunarResponse {
+    pub result: Vec<RankedLunar>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RankedLunar {
+    pub yyyymmdd: u64,
+    pub score: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListActivitiesResponse {
+    pub activities: Vec<(u16, String)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SolarTermsResponse {
+    /// `(solar term, yyyymmdd)` in calendar order
+    pub terms: Vec<(String, u64)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SolarTermOnResponse {
+    pub solar_term: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListFestivalsResponse {
+    /// `(festival, yyyymmdd)`
+    pub festivals: Vec<(String, u64)>,
+}
+
+/// Each `(field, value)` pair matches an indexed field. For `good_for` and `bad_for`,
+/// the value is the activity itself (in traditional or simplified characters), its
+/// English name or pinyin, or its numeric id in the dictionary. Either field can be narrowed
+/// to a double-hour, e.g. `good_for@子時` (or `good_for@子`) matches the activities of 子時.
+/// The day's clash animal and 煞 direction are indexed as `clash` and `sha`, and the clash
+/// animal of each double-hour as e.g. `clash@子時`. A clash written as e.g. 沖虎 is indexed
+/// and matched as the animal, 虎. The solar term starting on that day is
+/// indexed as `solar_term`, and each festival on that day as `festival`, e.g. `festival=中秋`.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Predicate {
     And(Vec<(String, String)>),
     AndNot(Vec<(String, String)>),
+    /// Excludes days that clash with the zodiac animal of `birth_year`. Someone born
+    /// before the lunar new year should give the year before.
+    NotClashingWith {
+        birth_year: i32,
+    },
 }
 
 impl Predicate {
@@ -50,6 +230,10 @@ impl Predicate {
     pub fn and_not(any_of: Vec<&str>) -> Self {
         Self::AndNot(into_tuples(any_of))
     }
+
+    pub fn not_clashing_with(birth_year: i32) -> Self {
+        Self::NotClashingWith { birth_year }
+    }
 }
 
 fn into_tuples(any_of: Vec<&str>) -> Vec<(String, String)> {
@@ -62,3 +246,22 @@ fn into_tuples(any_of: Vec<&str>) -> Vec<(String, String)> {
         })
         .collect::<Vec<_>>()
 }
+
+/// A weighted `(field, value)` pair for `RankLunar`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Preference {
+    pub field: String,
+    pub value: String,
+    pub weight: u32,
+}
+
+impl Preference {
+    pub fn new(kv: &str, weight: u32) -> Self {
+        let (field, value) = into_tuples(vec![kv]).remove(0);
+        Preference {
+            field,
+            value,
+            weight,
+        }
+    }
+}
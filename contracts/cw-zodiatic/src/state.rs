@@ -3,16 +3,49 @@ use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::Addr;
 use cw_storage_plus::{Item, Map};
+use zodiatic_bindings::Lunar;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
     pub owner: Addr,
+    /// Offset from UTC of the local time used to tell which day it is
+    #[serde(default = "default_utc_offset")]
+    pub utc_offset_minutes: i32,
+}
+
+/// UTC+8, the timezone of the almanac data
+pub fn default_utc_offset() -> i32 {
+    8 * 60
 }
 
 pub const STATE: Item<State> = Item::new("state");
 
-// "year[field=value]" -> bitmap
+// "year[field=value]" -> bitmap, where value is the activity id for activity fields
 pub const INDEX: Map<String, Vec<u32>> = Map::new("index");
 
-// year -> [yyyymmdd, ...]
+// year -> [yyyymmdd, ...], indexed by row id; deleted days keep their row as DELETED
 pub const KEYS: Map<u32, Vec<u64>> = Map::new("keys");
+
+pub const DELETED: u64 = 0;
+
+/// The last change to the index sent to the native module, to undo if the module rejects it.
+/// An accepted change gets no reply, so this stays until the next change replaces it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Pending {
+    pub yyyymmdd: u64,
+    pub row_id: u32,
+    /// The record that the row was indexed with before the change, if any
+    pub previous: Option<Lunar>,
+}
+
+pub const PENDING: Item<Pending> = Item::new("pending");
+
+// activity -> id, used in place of the activity string in INDEX keys. Former names of a
+// renamed activity keep its id.
+pub const ACTIVITIES: Map<&str, u16> = Map::new("activities");
+
+// id -> activity
+pub const ACTIVITY_NAMES: Map<u16, String> = Map::new("activity_names");
+
+// next unassigned activity id
+pub const ACTIVITY_COUNT: Item<u16> = Item::new("activity_count");
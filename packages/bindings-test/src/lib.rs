@@ -2,4 +2,7 @@ pub mod error;
 pub mod mock;
 mod multitest;
 
-pub use multitest::{ZodiaticApp, ZodiaticAppWrapped, ZodiaticError, ZodiaticModule};
+pub use multitest::{
+    ExecutedMsg, ZodiaticApp, ZodiaticAppBuilder, ZodiaticAppWrapped, ZodiaticError,
+    ZodiaticModule, ZodiaticSudo,
+};
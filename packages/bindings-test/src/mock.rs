@@ -1,14 +1,22 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::time::Duration;
 
 use cosmwasm_std::{
+    from_binary, from_slice,
     testing::{MockApi, MockStorage},
-    OwnedDeps, Querier,
+    to_binary, Binary, ContractResult, OwnedDeps, Querier, QuerierResult, QueryRequest, StdResult,
+    SystemError, SystemResult,
+};
+use serde::Serialize;
+use zodiatic_bindings::{
+    LunarBatchResponse, LunarExistsResponse, LunarRangeResponse, ZodiaticQuery,
 };
-use zodiatic_bindings::ZodiaticQuery;
 
 use crate::ZodiaticApp;
 
-fn mock_dependencies_with_custom_quierier<Q: Querier>(
+pub fn mock_dependencies_with_custom_querier<Q: Querier>(
     querier: Q,
 ) -> OwnedDeps<MockStorage, MockApi, Q, ZodiaticQuery> {
     OwnedDeps {
@@ -21,5 +29,257 @@ fn mock_dependencies_with_custom_quierier<Q: Querier>(
 
 pub fn mock_dependencies() -> OwnedDeps<MockStorage, MockApi, ZodiaticApp, ZodiaticQuery> {
     let custom_querier = ZodiaticApp::new();
-    mock_dependencies_with_custom_quierier(custom_querier)
+    mock_dependencies_with_custom_querier(custom_querier)
+}
+
+/// How a query that touches a day fails
+#[derive(Clone, Debug, PartialEq)]
+pub enum Failure {
+    /// The native module errors with the message
+    Error(String),
+    /// The native module answers as if it had no record of the day
+    Missing,
+    /// The native module leaves the day out of batch answers, which then have fewer entries
+    /// than keys, and otherwise answers as if it had no record of it
+    Dropped,
+}
+
+/// A querier that answers from a `ZodiaticApp`, but can make queries of given days fail and
+/// counts the queries it gets. Latency is simulated: it is added up, not waited for.
+pub struct MockZodiaticQuerier {
+    app: ZodiaticApp,
+    failures: HashMap<u64, Failure>,
+    latency_per_call: Duration,
+    latency_per_key: Duration,
+    calls: RefCell<Vec<ZodiaticQuery>>,
+    latency: Cell<Duration>,
+}
+
+impl Default for MockZodiaticQuerier {
+    fn default() -> Self {
+        Self::new(ZodiaticApp::new())
+    }
+}
+
+impl MockZodiaticQuerier {
+    /// Answers from the records of `app`
+    pub fn new(app: ZodiaticApp) -> Self {
+        MockZodiaticQuerier {
+            app,
+            failures: HashMap::new(),
+            latency_per_call: Duration::ZERO,
+            latency_per_key: Duration::ZERO,
+            calls: RefCell::new(vec![]),
+            latency: Cell::new(Duration::ZERO),
+        }
+    }
+
+    /// Makes every query that touches `yyyymmdd` fail as `failure`
+    pub fn with_failure(mut self, yyyymmdd: u64, failure: Failure) -> Self {
+        self.set_failure(yyyymmdd, failure);
+        self
+    }
+
+    pub fn set_failure(&mut self, yyyymmdd: u64, failure: Failure) {
+        self.failures.insert(yyyymmdd, failure);
+    }
+
+    pub fn clear_failures(&mut self) {
+        self.failures.clear();
+    }
+
+    /// Sets the simulated latency of each query, plus that of each record that it reads
+    pub fn with_latency(mut self, per_call: Duration, per_key: Duration) -> Self {
+        self.latency_per_call = per_call;
+        self.latency_per_key = per_key;
+        self
+    }
+
+    /// Returns every Zodiatic query received, oldest first
+    pub fn calls(&self) -> Vec<ZodiaticQuery> {
+        self.calls.borrow().clone()
+    }
+
+    pub fn call_count(&self) -> usize {
+        self.calls.borrow().len()
+    }
+
+    /// Returns the simulated latency of every Zodiatic query received
+    pub fn total_latency(&self) -> Duration {
+        self.latency.get()
+    }
+
+    pub fn reset_counters(&self) {
+        self.calls.borrow_mut().clear();
+        self.latency.set(Duration::ZERO);
+    }
+
+    fn record(&self, query: &ZodiaticQuery) {
+        // a range reads as many records as it returns, which are added when it does
+        let keys = match query {
+            ZodiaticQuery::Lunar { .. } | ZodiaticQuery::LunarExists { .. } => 1,
+            ZodiaticQuery::LunarBatch { keys } => keys.len(),
+            ZodiaticQuery::LunarRange { .. } | ZodiaticQuery::Params {} => 0,
+        };
+        self.add_latency(self.latency_per_call);
+        self.add_keys_latency(keys);
+        self.calls.borrow_mut().push(query.clone());
+    }
+
+    fn add_latency(&self, latency: Duration) {
+        self.latency.set(self.latency.get() + latency);
+    }
+
+    fn add_keys_latency(&self, keys: usize) {
+        self.add_latency(self.latency_per_key * keys as u32);
+    }
+
+    /// The days in `start..=end` that have a failure rule
+    fn days_in(&self, start: u64, end: u64) -> impl Iterator<Item = u64> + '_ {
+        self.failures
+            .keys()
+            .copied()
+            .filter(move |yyyymmdd| (start..=end).contains(yyyymmdd))
+    }
+
+    fn error_of(&self, keys: &[u64]) -> Option<String> {
+        keys.iter()
+            .find_map(|yyyymmdd| match self.failures.get(yyyymmdd) {
+                Some(Failure::Error(msg)) => Some(msg.clone()),
+                _ => None,
+            })
+    }
+
+    fn is_missing(&self, yyyymmdd: u64) -> bool {
+        matches!(
+            self.failures.get(&yyyymmdd),
+            Some(Failure::Missing | Failure::Dropped)
+        )
+    }
+
+    fn is_dropped(&self, yyyymmdd: u64) -> bool {
+        self.failures.get(&yyyymmdd) == Some(&Failure::Dropped)
+    }
+
+    fn query(&self, query: ZodiaticQuery) -> QuerierResult {
+        self.record(&query);
+        let keys = match &query {
+            ZodiaticQuery::Lunar { yyyymmdd } | ZodiaticQuery::LunarExists { yyyymmdd } => {
+                vec![*yyyymmdd]
+            }
+            ZodiaticQuery::LunarBatch { keys } => keys.clone(),
+            ZodiaticQuery::LunarRange { start, end } => self.days_in(*start, *end).collect(),
+            ZodiaticQuery::Params {} => vec![],
+        };
+        if let Some(msg) = self.error_of(&keys) {
+            return SystemResult::Ok(ContractResult::Err(msg));
+        }
+
+        match query {
+            ZodiaticQuery::Lunar { yyyymmdd } if self.is_missing(yyyymmdd) => {
+                SystemResult::Ok(ContractResult::Err(format!("Lunar {} not found", yyyymmdd)))
+            }
+            ZodiaticQuery::LunarExists { yyyymmdd } if self.is_missing(yyyymmdd) => {
+                respond(&LunarExistsResponse { exists: false })
+            }
+            ZodiaticQuery::LunarBatch { keys } => {
+                self.forward(ZodiaticQuery::LunarBatch { keys: keys.clone() }, |res| {
+                    let res: LunarBatchResponse = from_binary(&res)?;
+                    let lunars = keys
+                        .iter()
+                        .zip(res.lunars)
+                        .filter(|(yyyymmdd, _)| !self.is_dropped(**yyyymmdd))
+                        .map(|(yyyymmdd, lunar)| lunar.filter(|_| !self.is_missing(*yyyymmdd)))
+                        .collect();
+                    to_binary(&LunarBatchResponse { lunars })
+                })
+            }
+            ZodiaticQuery::LunarRange { start, end } => {
+                self.forward(ZodiaticQuery::LunarRange { start, end }, |res| {
+                    let mut res: LunarRangeResponse = from_binary(&res)?;
+                    self.add_keys_latency(res.lunars.len());
+                    res.lunars
+                        .retain(|(yyyymmdd, _)| !self.is_missing(*yyyymmdd));
+                    to_binary(&res)
+                })
+            }
+            query => self.forward(query, Ok),
+        }
+    }
+
+    /// Answers `query` from the app, passing a successful response through `edit`
+    fn forward(
+        &self,
+        query: ZodiaticQuery,
+        edit: impl FnOnce(Binary) -> StdResult<Binary>,
+    ) -> QuerierResult {
+        let request = QueryRequest::Custom(query);
+        let bin_request = match to_binary(&request) {
+            Ok(bin_request) => bin_request,
+            Err(err) => return system_error(err),
+        };
+        match self.app.raw_query(&bin_request) {
+            SystemResult::Ok(ContractResult::Ok(res)) => match edit(res) {
+                Ok(res) => SystemResult::Ok(ContractResult::Ok(res)),
+                Err(err) => system_error(err),
+            },
+            other => other,
+        }
+    }
+}
+
+impl Querier for MockZodiaticQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        match from_slice::<QueryRequest<ZodiaticQuery>>(bin_request) {
+            Ok(QueryRequest::Custom(query)) => self.query(query),
+            // everything but Zodiatic queries is answered by the app as it is
+            Ok(_) => self.app.raw_query(bin_request),
+            Err(err) => system_error(err),
+        }
+    }
+}
+
+fn respond<T: Serialize>(res: &T) -> QuerierResult {
+    match to_binary(res) {
+        Ok(res) => SystemResult::Ok(ContractResult::Ok(res)),
+        Err(err) => system_error(err),
+    }
+}
+
+fn system_error(err: impl ToString) -> QuerierResult {
+    SystemResult::Err(SystemError::InvalidRequest {
+        error: err.to_string(),
+        request: Binary::default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ZodiaticAppBuilder;
+    use cosmwasm_std::QuerierWrapper;
+    use zodiatic_bindings::{Lunar, ZodiaticQuerier};
+
+    #[test]
+    fn fails_ranges_that_touch_a_rule() {
+        let lunars = (20220101..=20220103).map(|yyyymmdd| (yyyymmdd, Lunar::default()));
+        let app = ZodiaticAppBuilder::new().with_lunars(lunars).build();
+        let mut querier = MockZodiaticQuerier::new(app)
+            .with_failure(20220102, Failure::Missing)
+            .with_latency(Duration::from_millis(20), Duration::from_millis(1));
+
+        let wrapper = QuerierWrapper::<ZodiaticQuery>::new(&querier);
+        let res = ZodiaticQuerier::new(&wrapper)
+            .lunar_range(20220101, 20220131)
+            .unwrap();
+        let keys: Vec<u64> = res.lunars.into_iter().map(|(key, _)| key).collect();
+        assert_eq!(vec![20220101, 20220103], keys);
+        assert_eq!(Duration::from_millis(23), querier.total_latency());
+
+        querier.set_failure(20220103, Failure::Error("module unavailable".into()));
+        let wrapper = QuerierWrapper::<ZodiaticQuery>::new(&querier);
+        let querier = ZodiaticQuerier::new(&wrapper);
+        querier.lunar_range(20220101, 20220131).unwrap_err();
+        querier.lunar_range(20220101, 20220102).unwrap();
+    }
 }
@@ -1,26 +1,123 @@
-use anyhow::{bail, Result as AnyResult};
+use anyhow::{bail, Context, Result as AnyResult};
 
 use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 
 use std::cmp::max;
 use std::fmt::Debug;
+use std::fs;
+use std::path::Path;
 
 use std::ops::{Deref, DerefMut};
 use thiserror::Error;
 
-use cosmwasm_std::testing::{MockApi, MockStorage};
+use cosmwasm_std::testing::{mock_env, MockApi, MockStorage};
 use cosmwasm_std::{
-    Addr, Api, Binary, BlockInfo, CustomQuery, Empty, Querier, QuerierResult, StdError, Storage,
+    from_slice, to_binary, Addr, Api, Binary, BlockInfo, Coin, CustomQuery, Order, Querier,
+    QuerierResult, StdError, StdResult, Storage, Timestamp,
 };
 use cw_multi_test::{
     App, AppResponse, BankKeeper, BasicAppBuilder, CosmosRouter, Module, WasmKeeper,
 };
+use cw_storage_plus::{Bound, Item, Map};
 
-use zodiatic_bindings::{ZodiaticMsg, ZodiaticQuery};
+use zodiatic_bindings::{
+    Lunar, LunarBatchResponse, LunarExistsResponse, LunarRangeResponse, LunarResponse, Params,
+    ParamsResponse, ZodiaticMsg, ZodiaticQuery,
+};
+
+/// yyyymmdd -> record, as the native module keeps it
+const LUNARS: Map<u64, Lunar> = Map::new("lunars");
+
+const PARAMS: Item<Params> = Item::new("params");
+
+/// Every message the module executed, oldest first
+const MESSAGES: Item<Vec<ExecutedMsg>> = Item::new("messages");
 
 pub struct ZodiaticModule {}
 
+/// What the chain's governance can do to the native module, without any account's key
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ZodiaticSudo {
+    /// Stores records, replacing any that exist
+    SetLunars {
+        lunars: Vec<(u64, Lunar)>,
+    },
+    SetParams {
+        params: Params,
+    },
+}
+
+/// A message that the native module executed. Messages that failed are not kept, as their
+/// transaction was rolled back.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExecutedMsg {
+    /// The contract or account that sent the message
+    pub sender: Addr,
+    pub height: u64,
+    pub time: Timestamp,
+    pub msg: ZodiaticMsg,
+}
+
+impl ZodiaticModule {
+    /// The params returned until `set_params` is called
+    pub fn default_params() -> Params {
+        Params {
+            first_year: 1901,
+            last_year: 2100,
+            data_source: "mock".to_string(),
+            permissioned_writes: false,
+        }
+    }
+
+    pub fn set_params(&self, storage: &mut dyn Storage, params: &Params) -> StdResult<()> {
+        PARAMS.save(storage, params)
+    }
+
+    /// Stores records, replacing any that exist
+    pub fn set_lunars(&self, storage: &mut dyn Storage, lunars: &[(u64, Lunar)]) -> StdResult<()> {
+        for (yyyymmdd, lunar) in lunars {
+            LUNARS.save(storage, *yyyymmdd, lunar)?;
+        }
+        Ok(())
+    }
+
+    /// Returns every message executed since the last `clear_messages`, oldest first
+    pub fn messages(&self, storage: &dyn Storage) -> StdResult<Vec<ExecutedMsg>> {
+        Ok(MESSAGES.may_load(storage)?.unwrap_or_default())
+    }
+
+    pub fn clear_messages(&self, storage: &mut dyn Storage) {
+        MESSAGES.remove(storage)
+    }
+
+    fn record_message(
+        &self,
+        storage: &mut dyn Storage,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: ZodiaticMsg,
+    ) -> StdResult<()> {
+        let mut messages = self.messages(storage)?;
+        messages.push(ExecutedMsg {
+            sender,
+            height: block.height,
+            time: block.time,
+            msg,
+        });
+        MESSAGES.save(storage, &messages)
+    }
+
+    fn load_lunar(&self, storage: &dyn Storage, yyyymmdd: u64) -> AnyResult<Lunar> {
+        match LUNARS.may_load(storage, yyyymmdd)? {
+            Some(lunar) => Ok(lunar),
+            None => bail!(ZodiaticError::LunarNotFound { yyyymmdd }),
+        }
+    }
+}
+
 /// How many seconds per block
 /// (when we increment block.height, use this multiplier for block.time)
 pub const BLOCK_TIME: u64 = 5;
@@ -28,49 +125,107 @@ pub const BLOCK_TIME: u64 = 5;
 impl Module for ZodiaticModule {
     type ExecT = ZodiaticMsg;
     type QueryT = ZodiaticQuery;
-    type SudoT = Empty;
+    type SudoT = ZodiaticSudo;
 
     // Builds a mock rust implementation of the expected Zodiatic functionality for testing
     fn execute<ExecC, QueryC>(
         &self,
         _api: &dyn Api,
-        _storage: &mut dyn Storage,
+        storage: &mut dyn Storage,
         _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
-        _block: &BlockInfo,
-        _sender: Addr,
-        _msg: ZodiaticMsg,
+        block: &BlockInfo,
+        sender: Addr,
+        msg: ZodiaticMsg,
     ) -> AnyResult<AppResponse>
     where
         ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
         QueryC: CustomQuery + DeserializeOwned + 'static,
     {
-        bail!("sudo not implemented for ZodiaticModule")
+        match msg.clone() {
+            ZodiaticMsg::CreateLunar {
+                yyyymmdd, lunar, ..
+            } => {
+                if LUNARS.has(storage, yyyymmdd) {
+                    bail!(ZodiaticError::LunarExists { yyyymmdd });
+                }
+                LUNARS.save(storage, yyyymmdd, &lunar)?;
+            }
+            ZodiaticMsg::UpdateLunar {
+                yyyymmdd, lunar, ..
+            } => {
+                self.load_lunar(storage, yyyymmdd)?;
+                LUNARS.save(storage, yyyymmdd, &lunar)?;
+            }
+            ZodiaticMsg::DeleteLunar { yyyymmdd, .. } => {
+                self.load_lunar(storage, yyyymmdd)?;
+                LUNARS.remove(storage, yyyymmdd);
+            }
+        }
+        self.record_message(storage, block, sender, msg)?;
+        Ok(AppResponse::default())
     }
 
     fn sudo<ExecC, QueryC>(
         &self,
         _api: &dyn Api,
-        _storage: &mut dyn Storage,
+        storage: &mut dyn Storage,
         _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
         _block: &BlockInfo,
-        _msg: Self::SudoT,
+        msg: Self::SudoT,
     ) -> AnyResult<AppResponse>
     where
         ExecC: Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
         QueryC: CustomQuery + DeserializeOwned + 'static,
     {
-        bail!("sudo not implemented for ZodiaticModule")
+        match msg {
+            ZodiaticSudo::SetLunars { lunars } => self.set_lunars(storage, &lunars)?,
+            ZodiaticSudo::SetParams { params } => self.set_params(storage, &params)?,
+        }
+        Ok(AppResponse::default())
     }
 
     fn query(
         &self,
         _api: &dyn Api,
-        _storage: &dyn Storage,
+        storage: &dyn Storage,
         _querier: &dyn Querier,
         _block: &BlockInfo,
-        _request: ZodiaticQuery,
+        request: ZodiaticQuery,
     ) -> anyhow::Result<Binary> {
-        bail!("sudo not implemented for ZodiaticModule")
+        match request {
+            ZodiaticQuery::Lunar { yyyymmdd } => {
+                let lunar = self.load_lunar(storage, yyyymmdd)?;
+                Ok(to_binary(&LunarResponse { lunar })?)
+            }
+            ZodiaticQuery::LunarRange { start, end } => {
+                let lunars = LUNARS
+                    .range(
+                        storage,
+                        Some(Bound::inclusive(start)),
+                        Some(Bound::inclusive(end)),
+                        Order::Ascending,
+                    )
+                    .collect::<StdResult<Vec<_>>>()?;
+                Ok(to_binary(&LunarRangeResponse { lunars })?)
+            }
+            ZodiaticQuery::LunarBatch { keys } => {
+                let lunars = keys
+                    .into_iter()
+                    .map(|yyyymmdd| LUNARS.may_load(storage, yyyymmdd))
+                    .collect::<StdResult<Vec<_>>>()?;
+                Ok(to_binary(&LunarBatchResponse { lunars })?)
+            }
+            ZodiaticQuery::LunarExists { yyyymmdd } => {
+                let exists = LUNARS.has(storage, yyyymmdd);
+                Ok(to_binary(&LunarExistsResponse { exists })?)
+            }
+            ZodiaticQuery::Params {} => {
+                let params = PARAMS
+                    .may_load(storage)?
+                    .unwrap_or_else(Self::default_params);
+                Ok(to_binary(&ParamsResponse { params })?)
+            }
+        }
     }
 }
 
@@ -82,6 +237,12 @@ pub enum ZodiaticError {
     #[error("{0}")]
     Overflow(#[from] cosmwasm_std::OverflowError),
 
+    #[error("Lunar {yyyymmdd} already exists")]
+    LunarExists { yyyymmdd: u64 },
+
+    #[error("Lunar {yyyymmdd} not found")]
+    LunarNotFound { yyyymmdd: u64 },
+
     /// Remove this to let the compiler find all TODOs
     #[error("Not yet implemented (TODO)")]
     Unimplemented,
@@ -90,25 +251,29 @@ pub enum ZodiaticError {
 pub type ZodiaticAppWrapped =
     App<BankKeeper, MockApi, MockStorage, ZodiaticModule, WasmKeeper<ZodiaticMsg, ZodiaticQuery>>;
 
-pub struct ZodiaticApp(ZodiaticAppWrapped);
+pub struct ZodiaticApp {
+    app: ZodiaticAppWrapped,
+    /// How many seconds per block
+    block_time: u64,
+}
 
 impl Deref for ZodiaticApp {
     type Target = ZodiaticAppWrapped;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.app
     }
 }
 
 impl DerefMut for ZodiaticApp {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        &mut self.app
     }
 }
 
 impl Querier for ZodiaticApp {
     fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
-        self.0.raw_query(bin_request)
+        self.app.raw_query(bin_request)
     }
 }
 
@@ -120,24 +285,44 @@ impl Default for ZodiaticApp {
 
 impl ZodiaticApp {
     pub fn new() -> Self {
-        Self(
-            BasicAppBuilder::<ZodiaticMsg, ZodiaticQuery>::new_custom()
-                .with_custom(ZodiaticModule {})
-                .build(|_router, _, _storage| {
-                    // router.custom.set_owner(storage, &owner).unwrap();
-                }),
-        )
+        ZodiaticAppBuilder::new().build()
+    }
+
+    /// Sets the params that the mock module reports
+    pub fn set_params(&mut self, params: &Params) -> StdResult<()> {
+        self.init_modules(|router, _, storage| router.custom.set_params(storage, params))
+    }
+
+    /// Runs `msg` on the native module as governance would. Use `wasm_sudo` to call the
+    /// `sudo` entry point of a contract.
+    pub fn zodiatic_sudo(&mut self, msg: ZodiaticSudo) -> AnyResult<AppResponse> {
+        let block = self.block_info();
+        self.init_modules(|router, api, storage| {
+            router.custom.sudo(api, storage, &*router, &block, msg)
+        })
+    }
+
+    /// Returns every message that the native module executed since the app was built or
+    /// `clear_zodiatic_messages` was last called, oldest first
+    pub fn zodiatic_messages(&self) -> Vec<ExecutedMsg> {
+        self.read_module(|router, _, storage| router.custom.messages(storage))
+            .unwrap()
+    }
+
+    pub fn clear_zodiatic_messages(&mut self) {
+        self.init_modules(|router, _, storage| router.custom.clear_messages(storage))
     }
 
     pub fn block_info(&self) -> BlockInfo {
-        self.0.block_info()
+        self.app.block_info()
     }
 
     /// This advances BlockInfo by given number of blocks.
     /// It does not do any callbacks, but keeps the ratio of seconds/block
     pub fn advance_blocks(&mut self, blocks: u64) {
+        let block_time = self.block_time;
         self.update_block(|block| {
-            block.time = block.time.plus_seconds(BLOCK_TIME * blocks);
+            block.time = block.time.plus_seconds(block_time * blocks);
             block.height += blocks;
         });
     }
@@ -145,9 +330,10 @@ impl ZodiaticApp {
     /// This advances BlockInfo by given number of seconds.
     /// It does not do any callbacks, but keeps the ratio of seconds/block
     pub fn advance_seconds(&mut self, seconds: u64) {
+        let block_time = self.block_time;
         self.update_block(|block| {
             block.time = block.time.plus_seconds(seconds);
-            block.height += max(1, seconds / BLOCK_TIME);
+            block.height += max(1, seconds / block_time);
         });
     }
 
@@ -157,3 +343,141 @@ impl ZodiaticApp {
         self.advance_blocks(1)
     }
 }
+
+/// Builds a `ZodiaticApp` that starts with records, balances and block settings of its own
+pub struct ZodiaticAppBuilder {
+    lunars: Vec<(u64, Lunar)>,
+    params: Option<Params>,
+    balances: Vec<(Addr, Vec<Coin>)>,
+    block_time: u64,
+    start_time: Option<Timestamp>,
+}
+
+impl Default for ZodiaticAppBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZodiaticAppBuilder {
+    pub fn new() -> Self {
+        ZodiaticAppBuilder {
+            lunars: vec![],
+            params: None,
+            balances: vec![],
+            block_time: BLOCK_TIME,
+            start_time: None,
+        }
+    }
+
+    /// Adds records to the native module, replacing any added before for the same day
+    pub fn with_lunars(mut self, lunars: impl IntoIterator<Item = (u64, Lunar)>) -> Self {
+        self.lunars.extend(lunars);
+        self
+    }
+
+    /// Adds the records of a JSON file of `[yyyymmdd, record]` pairs, the format of
+    /// `LunarRangeResponse::lunars`
+    pub fn with_lunars_from_json(self, path: impl AsRef<Path>) -> AnyResult<Self> {
+        let path = path.as_ref();
+        let json = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+        let lunars: Vec<(u64, Lunar)> =
+            from_slice(&json).with_context(|| format!("parsing {}", path.display()))?;
+        Ok(self.with_lunars(lunars))
+    }
+
+    pub fn with_params(mut self, params: Params) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    /// Gives `addr` an initial balance of `coins`
+    pub fn with_balance(mut self, addr: &str, coins: Vec<Coin>) -> Self {
+        self.balances.push((Addr::unchecked(addr), coins));
+        self
+    }
+
+    /// Sets how many seconds `advance_blocks` moves the block time per block
+    pub fn with_block_time(mut self, seconds: u64) -> Self {
+        self.block_time = seconds;
+        self
+    }
+
+    /// Sets the block time of the first block
+    pub fn with_start_time(mut self, time: Timestamp) -> Self {
+        self.start_time = Some(time);
+        self
+    }
+
+    pub fn build(self) -> ZodiaticApp {
+        let mut block = mock_env().block;
+        if let Some(time) = self.start_time {
+            block.time = time;
+        }
+        let app = BasicAppBuilder::<ZodiaticMsg, ZodiaticQuery>::new_custom()
+            .with_custom(ZodiaticModule {})
+            .with_block(block)
+            .build(|router, _, storage| {
+                router.custom.set_lunars(storage, &self.lunars).unwrap();
+                if let Some(params) = &self.params {
+                    router.custom.set_params(storage, params).unwrap();
+                }
+                for (addr, coins) in self.balances {
+                    router.bank.init_balance(storage, &addr, coins).unwrap();
+                }
+            });
+        ZodiaticApp {
+            app,
+            block_time: self.block_time,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::coins;
+    use zodiatic_bindings::ZodiaticQuerier;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/lunars-2022-01.json");
+
+    #[test]
+    fn builds_seeded_app() {
+        let start = Timestamp::from_seconds(1_640_966_400); // 2022-01-01T00:00:00Z
+        let lunar = Lunar::new("2022-01-02", (2021, 11, 30), "", "", "", "嫁娶", "");
+        let mut app = ZodiaticAppBuilder::new()
+            .with_lunars_from_json(FIXTURE)
+            .unwrap()
+            .with_lunars(vec![(20220102, lunar.clone())])
+            .with_balance("alice", coins(100, "zod"))
+            .with_block_time(6)
+            .with_start_time(start)
+            .build();
+
+        let wrapper = app.wrap();
+        let res = ZodiaticQuerier::new(&wrapper)
+            .lunar_range(20220101, 20220131)
+            .unwrap();
+        let keys: Vec<u64> = res.lunars.iter().map(|(key, _)| *key).collect();
+        assert_eq!(vec![20220101, 20220102, 20220131], keys);
+        assert_eq!("猴", res.lunars[0].1.clash);
+        assert_eq!(lunar, res.lunars[1].1);
+        assert_eq!(
+            coins(100, "zod"),
+            vec![app.wrap().query_balance("alice", "zod").unwrap()]
+        );
+
+        assert_eq!(start, app.block_info().time);
+        app.advance_blocks(10);
+        assert_eq!(start.plus_seconds(60), app.block_info().time);
+    }
+
+    #[test]
+    fn reports_bad_fixtures() {
+        let err = ZodiaticAppBuilder::new()
+            .with_lunars_from_json("no/such/file.json")
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("no/such/file.json"));
+    }
+}
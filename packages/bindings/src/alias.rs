@@ -0,0 +1,176 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::convert::to_traditional;
+
+/// Languages that activities can be translated into
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Language {
+    English,
+    Pinyin,
+}
+
+/// Canonical activity, English name and toneless pinyin with syllables separated by spaces
+const ACTIVITY_ALIASES: &[(&str, &str, &str)] = &[
+    ("祭祀", "offering sacrifices", "ji si"),
+    ("祈福", "praying for blessings", "qi fu"),
+    ("求嗣", "praying for offspring", "qiu si"),
+    ("開光", "consecration", "kai guang"),
+    ("齋醮", "taoist rites", "zhai jiao"),
+    ("出行", "travel", "chu xing"),
+    ("解除", "cleansing", "jie chu"),
+    ("嫁娶", "marriage", "jia qu"),
+    ("納采", "betrothal gifts", "na cai"),
+    ("訂盟", "engagement", "ding meng"),
+    ("納婿", "taking a son-in-law", "na xu"),
+    ("冠笄", "coming of age", "guan ji"),
+    ("冠帶", "capping ceremony", "guan dai"),
+    ("會親友", "visiting relatives", "hui qin you"),
+    ("宴會", "banquet", "yan hui"),
+    ("沐浴", "bathing", "mu yu"),
+    ("剃頭", "haircut", "ti tou"),
+    ("整手足甲", "manicure", "zheng shou zu jia"),
+    ("求醫療病", "medical treatment", "qiu yi liao bing"),
+    ("針灸", "acupuncture", "zhen jiu"),
+    ("上表章", "submitting petitions", "shang biao zhang"),
+    ("上官", "taking office", "shang guan"),
+    ("赴任", "starting a post", "fu ren"),
+    ("入學", "starting school", "ru xue"),
+    ("習藝", "learning a craft", "xi yi"),
+    ("招賢", "recruiting talent", "zhao xian"),
+    ("施恩", "granting favours", "shi en"),
+    ("慶賜", "granting rewards", "qing ci"),
+    ("進人口", "taking in household members", "jin ren kou"),
+    ("開市", "opening business", "kai shi"),
+    ("立券", "signing contracts", "li quan"),
+    ("交易", "trading", "jiao yi"),
+    ("納財", "receiving wealth", "na cai"),
+    ("開倉庫", "opening storehouses", "kai cang ku"),
+    ("出貨財", "shipping goods", "chu huo cai"),
+    ("修倉庫", "repairing storehouses", "xiu cang ku"),
+    ("訴訟", "litigation", "su song"),
+    ("詞訟", "lawsuits", "ci song"),
+    ("移徙", "moving house", "yi xi"),
+    ("入宅", "moving in", "ru zhai"),
+    ("安床", "placing a bed", "an chuang"),
+    ("安門", "installing doors", "an men"),
+    ("作灶", "building a stove", "zuo zao"),
+    ("安香", "installing incense", "an xiang"),
+    ("掃舍宇", "house cleaning", "sao she yu"),
+    ("修造", "renovation", "xiu zao"),
+    ("動土", "breaking ground", "dong tu"),
+    ("破土", "grave digging", "po tu"),
+    ("豎柱上梁", "raising the roof beam", "shu zhu shang liang"),
+    ("上梁", "roof beam", "shang liang"),
+    ("修置產室", "preparing a delivery room", "xiu zhi chan shi"),
+    ("修飾垣牆", "decorating walls", "xiu shi yuan qiang"),
+    ("補垣", "mending walls", "bu yuan"),
+    ("破屋壞垣", "demolition", "po wu huai yuan"),
+    ("苫蓋", "thatching", "shan gai"),
+    ("安碓磑", "installing mills", "an dui wei"),
+    ("平治道塗", "road repair", "ping zhi dao tu"),
+    ("築堤防", "building embankments", "zhu di fang"),
+    ("造橋", "bridge building", "zao qiao"),
+    ("開渠", "digging channels", "kai qu"),
+    ("穿井", "digging wells", "chuan jing"),
+    ("塞穴", "blocking holes", "sai xue"),
+    ("鼓鑄", "metal casting", "gu zhu"),
+    ("裁衣", "tailoring", "cai yi"),
+    ("經絡", "spinning", "jing luo"),
+    ("醞釀", "brewing", "yun niang"),
+    ("栽種", "planting", "zai zhong"),
+    ("伐木", "logging", "fa mu"),
+    ("牧養", "animal husbandry", "mu yang"),
+    ("納畜", "buying livestock", "na chu"),
+    ("畋獵", "hunting", "tian lie"),
+    ("捕捉", "trapping", "bu zhuo"),
+    ("結網", "net making", "jie wang"),
+    ("取魚", "fishing", "qu yu"),
+    ("乘船渡水", "boat crossing", "cheng chuan du shui"),
+    ("遠回", "returning from afar", "yuan hui"),
+    ("謝土", "thanking the earth god", "xie tu"),
+    ("啟攢", "exhumation", "qi zan"),
+    ("入殮", "encoffining", "ru lian"),
+    ("移柩", "moving the coffin", "yi jiu"),
+    ("成服", "wearing mourning", "cheng fu"),
+    ("除服", "ending mourning", "chu fu"),
+    ("安葬", "burial", "an zang"),
+];
+
+/// Returns the canonical activity for an English name or pinyin alias, ignoring case and
+/// spacing (e.g. "Marriage", "jia qu" and "jiaqu" all give 嫁娶).
+///
+/// Toneless pinyin shared by several activities (納采 and 納財 are both "na cai") is
+/// ambiguous and resolves to nothing; use the English name instead.
+pub fn resolve_alias(alias: &str) -> Option<&'static str> {
+    let alias = alias.trim().to_lowercase();
+    if let Some((activity, _, _)) = ACTIVITY_ALIASES
+        .iter()
+        .find(|(_, english, _)| *english == alias)
+    {
+        return Some(activity);
+    }
+
+    let syllables = squash(&alias);
+    let mut matches = ACTIVITY_ALIASES
+        .iter()
+        .filter(|(_, _, pinyin)| squash(pinyin) == syllables);
+    match (matches.next(), matches.next()) {
+        (Some((activity, _, _)), None) => Some(activity),
+        _ => None,
+    }
+}
+
+/// Returns the translation of a canonical activity, in either script
+pub fn translate(activity: &str, lang: Language) -> Option<&'static str> {
+    let activity = to_traditional(activity);
+    ACTIVITY_ALIASES
+        .iter()
+        .find(|(canonical, _, _)| *canonical == activity)
+        .map(|(_, english, pinyin)| match lang {
+            Language::English => *english,
+            Language::Pinyin => *pinyin,
+        })
+}
+
+fn squash(pinyin: &str) -> String {
+    pinyin
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_english_and_pinyin() {
+        assert_eq!(Some("嫁娶"), resolve_alias("marriage"));
+        assert_eq!(Some("嫁娶"), resolve_alias(" Marriage "));
+        assert_eq!(Some("嫁娶"), resolve_alias("jia qu"));
+        assert_eq!(Some("嫁娶"), resolve_alias("JiaQu"));
+        assert_eq!(Some("乘船渡水"), resolve_alias("cheng-chuan-du-shui"));
+        assert_eq!(None, resolve_alias("na cai"));
+        assert_eq!(Some("納財"), resolve_alias("receiving wealth"));
+        assert_eq!(None, resolve_alias("嫁娶"));
+    }
+
+    #[test]
+    fn translates_canonical_activities() {
+        assert_eq!(
+            Some("opening business"),
+            translate("開市", Language::English)
+        );
+        assert_eq!(Some("kai shi"), translate("开市", Language::Pinyin));
+        assert_eq!(None, translate("marriage", Language::English));
+    }
+
+    #[test]
+    fn english_names_are_lowercase() {
+        assert!(ACTIVITY_ALIASES
+            .iter()
+            .all(|(_, english, _)| *english == english.to_lowercase()));
+    }
+}
@@ -0,0 +1,305 @@
+/// Simplified to traditional characters for the almanac vocabulary, sorted by the
+/// simplified character. Characters with more than one traditional form are left out,
+/// except where the almanac only uses one of them (e.g. 谷 as in 穀雨).
+const SIMPLIFIED_TO_TRADITIONAL: &[(char, char)] = &[
+    ('东', '東'),
+    ('两', '兩'),
+    ('丧', '喪'),
+    ('举', '舉'),
+    ('习', '習'),
+    ('乡', '鄉'),
+    ('书', '書'),
+    ('买', '買'),
+    ('产', '產'),
+    ('亲', '親'),
+    ('仓', '倉'),
+    ('会', '會'),
+    ('债', '債'),
+    ('养', '養'),
+    ('冲', '沖'),
+    ('动', '動'),
+    ('医', '醫'),
+    ('卖', '賣'),
+    ('厕', '廁'),
+    ('发', '發'),
+    ('启', '啟'),
+    ('园', '園'),
+    ('围', '圍'),
+    ('场', '場'),
+    ('坏', '壞'),
+    ('坛', '壇'),
+    ('坟', '墳'),
+    ('墙', '牆'),
+    ('处', '處'),
+    ('头', '頭'),
+    ('学', '學'),
+    ('宝', '寶'),
+    ('宫', '宮'),
+    ('宾', '賓'),
+    ('对', '對'),
+    ('导', '導'),
+    ('将', '將'),
+    ('岁', '歲'),
+    ('岗', '崗'),
+    ('师', '師'),
+    ('帐', '帳'),
+    ('帘', '簾'),
+    ('带', '帶'),
+    ('庆', '慶'),
+    ('库', '庫'),
+    ('庙', '廟'),
+    ('开', '開'),
+    ('张', '張'),
+    ('归', '歸'),
+    ('录', '錄'),
+    ('恳', '懇'),
+    ('惊', '驚'),
+    ('愿', '願'),
+    ('扫', '掃'),
+    ('择', '擇'),
+    ('挂', '掛'),
+    ('捡', '撿'),
+    ('据', '據'),
+    ('摆', '擺'),
+    ('斋', '齋'),
+    ('断', '斷'),
+    ('无', '無'),
+    ('时', '時'),
+    ('栅', '柵'),
+    ('栏', '欄'),
+    ('桥', '橋'),
+    ('殓', '殮'),
+    ('气', '氣'),
+    ('汤', '湯'),
+    ('浇', '澆'),
+    ('涂', '塗'),
+    ('渔', '漁'),
+    ('满', '滿'),
+    ('灵', '靈'),
+    ('灶', '竈'),
+    ('灾', '災'),
+    ('烟', '煙'),
+    ('烧', '燒'),
+    ('热', '熱'),
+    ('爷', '爺'),
+    ('犹', '猶'),
+    ('猎', '獵'),
+    ('猪', '豬'),
+    ('献', '獻'),
+    ('环', '環'),
+    ('现', '現'),
+    ('画', '畫'),
+    ('畅', '暢'),
+    ('疗', '療'),
+    ('监', '監'),
+    ('盖', '蓋'),
+    ('盘', '盤'),
+    ('砖', '磚'),
+    ('硙', '磑'),
+    ('礼', '禮'),
+    ('祸', '禍'),
+    ('离', '離'),
+    ('种', '種'),
+    ('穷', '窮'),
+    ('窑', '窯'),
+    ('竖', '豎'),
+    ('竞', '競'),
+    ('笔', '筆'),
+    ('筑', '築'),
+    ('签', '簽'),
+    ('粮', '糧'),
+    ('约', '約'),
+    ('纪', '紀'),
+    ('纳', '納'),
+    ('纸', '紙'),
+    ('线', '線'),
+    ('经', '經'),
+    ('结', '結'),
+    ('络', '絡'),
+    ('绣', '繡'),
+    ('续', '續'),
+    ('缝', '縫'),
+    ('网', '網'),
+    ('罗', '羅'),
+    ('肃', '肅'),
+    ('胜', '勝'),
+    ('腊', '臘'),
+    ('舆', '輿'),
+    ('舰', '艦'),
+    ('艰', '艱'),
+    ('艺', '藝'),
+    ('节', '節'),
+    ('芦', '蘆'),
+    ('苏', '蘇'),
+    ('荐', '薦'),
+    ('药', '藥'),
+    ('莱', '萊'),
+    ('获', '獲'),
+    ('营', '營'),
+    ('蚕', '蠶'),
+    ('蛰', '蟄'),
+    ('补', '補'),
+    ('观', '觀'),
+    ('规', '規'),
+    ('视', '視'),
+    ('览', '覽'),
+    ('觉', '覺'),
+    ('计', '計'),
+    ('订', '訂'),
+    ('认', '認'),
+    ('讨', '討'),
+    ('让', '讓'),
+    ('议', '議'),
+    ('讲', '講'),
+    ('许', '許'),
+    ('论', '論'),
+    ('讼', '訟'),
+    ('设', '設'),
+    ('访', '訪'),
+    ('证', '證'),
+    ('识', '識'),
+    ('诉', '訴'),
+    ('词', '詞'),
+    ('诏', '詔'),
+    ('译', '譯'),
+    ('试', '試'),
+    ('诗', '詩'),
+    ('诚', '誠'),
+    ('话', '話'),
+    ('询', '詢'),
+    ('该', '該'),
+    ('详', '詳'),
+    ('语', '語'),
+    ('说', '說'),
+    ('请', '請'),
+    ('读', '讀'),
+    ('课', '課'),
+    ('谈', '談'),
+    ('谋', '謀'),
+    ('谢', '謝'),
+    ('谨', '謹'),
+    ('谷', '穀'),
+    ('贞', '貞'),
+    ('负', '負'),
+    ('贡', '貢'),
+    ('财', '財'),
+    ('贤', '賢'),
+    ('货', '貨'),
+    ('贵', '貴'),
+    ('贺', '賀'),
+    ('资', '資'),
+    ('赏', '賞'),
+    ('赐', '賜'),
+    ('赠', '贈'),
+    ('赵', '趙'),
+    ('趋', '趨'),
+    ('跃', '躍'),
+    ('车', '車'),
+    ('轿', '轎'),
+    ('辞', '辭'),
+    ('边', '邊'),
+    ('达', '達'),
+    ('迁', '遷'),
+    ('过', '過'),
+    ('运', '運'),
+    ('还', '還'),
+    ('这', '這'),
+    ('进', '進'),
+    ('远', '遠'),
+    ('违', '違'),
+    ('连', '連'),
+    ('选', '選'),
+    ('递', '遞'),
+    ('遗', '遺'),
+    ('邻', '鄰'),
+    ('郑', '鄭'),
+    ('酝', '醞'),
+    ('酱', '醬'),
+    ('酿', '釀'),
+    ('释', '釋'),
+    ('针', '針'),
+    ('钟', '鐘'),
+    ('钥', '鑰'),
+    ('钱', '錢'),
+    ('铁', '鐵'),
+    ('银', '銀'),
+    ('铺', '鋪'),
+    ('锁', '鎖'),
+    ('镇', '鎮'),
+    ('长', '長'),
+    ('门', '門'),
+    ('闭', '閉'),
+    ('问', '問'),
+    ('闰', '閏'),
+    ('闲', '閑'),
+    ('间', '間'),
+    ('闻', '聞'),
+    ('队', '隊'),
+    ('阳', '陽'),
+    ('阴', '陰'),
+    ('际', '際'),
+    ('陈', '陳'),
+    ('险', '險'),
+    ('随', '隨'),
+    ('隐', '隱'),
+    ('难', '難'),
+    ('雾', '霧'),
+    ('静', '靜'),
+    ('页', '頁'),
+    ('顶', '頂'),
+    ('顺', '順'),
+    ('须', '須'),
+    ('预', '預'),
+    ('领', '領'),
+    ('颜', '顏'),
+    ('风', '風'),
+    ('飞', '飛'),
+    ('饭', '飯'),
+    ('饮', '飲'),
+    ('饰', '飾'),
+    ('馆', '館'),
+    ('马', '馬'),
+    ('驱', '驅'),
+    ('驾', '駕'),
+    ('验', '驗'),
+    ('鱼', '魚'),
+    ('鸟', '鳥'),
+    ('鸡', '雞'),
+    ('黄', '黃'),
+    ('齐', '齊'),
+    ('齿', '齒'),
+    ('龙', '龍'),
+    ('龟', '龜'),
+];
+
+/// Converts simplified characters in `text` to their traditional forms, which is the script
+/// used by the almanac data. Every other character is kept as is.
+pub fn to_traditional(text: &str) -> String {
+    text.chars().map(traditional_char).collect()
+}
+
+fn traditional_char(c: char) -> char {
+    SIMPLIFIED_TO_TRADITIONAL
+        .binary_search_by_key(&c, |(simplified, _)| *simplified)
+        .map(|i| SIMPLIFIED_TO_TRADITIONAL[i].1)
+        .unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_is_sorted() {
+        assert!(SIMPLIFIED_TO_TRADITIONAL
+            .windows(2)
+            .all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn converts_simplified_only() {
+        assert_eq!("辛丑 豎柱上梁 開市", to_traditional("辛丑 竖柱上梁 开市"));
+        assert_eq!("豎柱上梁 納財", to_traditional("豎柱上梁 納財"));
+        assert_eq!("marriage", to_traditional("marriage"));
+    }
+}
@@ -0,0 +1,98 @@
+use crate::convert::to_traditional;
+use crate::solar_term::solar_term_on;
+use crate::types::Lunar;
+
+/// When a festival falls
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FestivalRule {
+    /// A day of a lunar month. Leap months (閏月) have no festivals.
+    LunarDate { month: u64, day: u64 },
+    /// The last day of the 12th lunar month, i.e. the 29th or the 30th depending on its size
+    LunarYearEnd,
+    /// The day that a solar term starts
+    SolarTerm(&'static str),
+}
+
+/// The traditional festivals, in the order they fall in a lunar year. 小年 follows the
+/// northern custom of the 23rd.
+pub const FESTIVALS: &[(&str, FestivalRule)] = &[
+    ("春節", FestivalRule::LunarDate { month: 1, day: 1 }),
+    ("元宵", FestivalRule::LunarDate { month: 1, day: 15 }),
+    ("龍抬頭", FestivalRule::LunarDate { month: 2, day: 2 }),
+    ("上巳", FestivalRule::LunarDate { month: 3, day: 3 }),
+    ("清明", FestivalRule::SolarTerm("清明")),
+    ("端午", FestivalRule::LunarDate { month: 5, day: 5 }),
+    ("七夕", FestivalRule::LunarDate { month: 7, day: 7 }),
+    ("中元", FestivalRule::LunarDate { month: 7, day: 15 }),
+    ("中秋", FestivalRule::LunarDate { month: 8, day: 15 }),
+    ("重陽", FestivalRule::LunarDate { month: 9, day: 9 }),
+    ("寒衣", FestivalRule::LunarDate { month: 10, day: 1 }),
+    ("下元", FestivalRule::LunarDate { month: 10, day: 15 }),
+    ("冬至", FestivalRule::SolarTerm("冬至")),
+    ("臘八", FestivalRule::LunarDate { month: 12, day: 8 }),
+    ("小年", FestivalRule::LunarDate { month: 12, day: 23 }),
+    ("除夕", FestivalRule::LunarYearEnd),
+];
+
+/// Returns the festivals that fall on `yyyymmdd`, whose almanac is `lunar`.
+///
+/// The lunar month and day come from `lunar.lunar_number`. Whether the month is a leap month
+/// (閏) and whether it is long (大) or short (小) are read from the `lunar` text, e.g.
+/// "臘月大初一".
+pub fn festivals(yyyymmdd: u64, lunar: &Lunar) -> Vec<&'static str> {
+    let (_, month, day) = lunar.lunar_number;
+    let text = to_traditional(&lunar.lunar);
+    let leap = text.contains('閏');
+    let short = text.contains("月小");
+    FESTIVALS
+        .iter()
+        .filter(|(_, rule)| match rule {
+            FestivalRule::LunarDate { month: m, day: d } => !leap && month == *m && day == *d,
+            FestivalRule::LunarYearEnd => !leap && month == 12 && (day == 30 || short && day == 29),
+            FestivalRule::SolarTerm(term) => solar_term_on(yyyymmdd) == Some(*term),
+        })
+        .map(|(name, _)| *name)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lunar(lunar_number: (u64, u64, u64), text: &str) -> Lunar {
+        Lunar {
+            lunar_number,
+            lunar: text.into(),
+            ..Lunar::default()
+        }
+    }
+
+    #[test]
+    fn finds_lunar_date_festivals() {
+        let day = lunar((2022, 1, 1), "二零二二 壬寅[虎]年 正月大初一");
+        assert_eq!(vec!["春節"], festivals(20220201, &day));
+        let day = lunar((2022, 8, 15), "二零二二 壬寅[虎]年 八月小十五");
+        assert_eq!(vec!["中秋"], festivals(20220910, &day));
+        let day = lunar((2022, 8, 16), "二零二二 壬寅[虎]年 八月小十六");
+        assert!(festivals(20220911, &day).is_empty());
+
+        // the 5th of a leap 5th month is not 端午
+        let day = lunar((2009, 5, 5), "二零零九 己醜[牛]年 闰五月小初五");
+        assert!(festivals(20090627, &day).is_empty());
+    }
+
+    #[test]
+    fn finds_year_end_and_solar_term_festivals() {
+        let day = lunar((2021, 12, 29), "二零二一 辛醜[牛]年 臘月小廿九");
+        assert_eq!(vec!["除夕"], festivals(20220131, &day));
+        let day = lunar((2022, 12, 29), "二零二二 壬寅[虎]年 臘月大廿九");
+        assert!(festivals(20230120, &day).is_empty());
+        let day = lunar((2022, 12, 30), "二零二二 壬寅[虎]年 臘月大三十");
+        assert_eq!(vec!["除夕"], festivals(20230121, &day));
+
+        let day = lunar((2022, 3, 5), "二零二二 壬寅[虎]年 三月大初五");
+        assert_eq!(vec!["清明"], festivals(20220405, &day));
+        let day = lunar((2022, 11, 29), "二零二二 壬寅[虎]年 冬月大廿九");
+        assert_eq!(vec!["冬至"], festivals(20221222, &day));
+    }
+}
@@ -1,14 +1,30 @@
+mod alias;
+mod convert;
+mod festival;
 mod msg;
+#[cfg(feature = "stargate")]
+pub mod proto;
 mod querier;
 mod query;
+mod solar_term;
 mod types;
+mod zodiac;
 
+pub use alias::{resolve_alias, translate, Language};
+pub use convert::to_traditional;
+pub use festival::{festivals, FestivalRule, FESTIVALS};
 pub use msg::ZodiaticMsg;
 pub use querier::ZodiaticQuerier;
-pub use query::{LunarResponse, ZodiaticQuery};
-pub use types::Lunar;
+pub use query::{
+    LunarBatchResponse, LunarExistsResponse, LunarRangeResponse, LunarResponse, Params,
+    ParamsResponse, ZodiaticQuery,
+};
+pub use solar_term::{solar_term_on, solar_terms, SOLAR_TERMS, SOLAR_TERM_YEARS};
+pub use types::{hour_name, Lunar, LunarHour, HOURS};
+pub use zodiac::{animal_name, zodiac_animal, ANIMALS};
 
 // This is a signal, such that any contract that imports these helpers will only run on the
 // zodiatic blockchain
+#[cfg(feature = "requires-zodiatic")]
 #[no_mangle]
 extern "C" fn requires_zodiatic() {}
@@ -14,6 +14,14 @@ pub enum ZodiaticMsg {
         yyyymmdd: u64,
         lunar: Lunar,
     },
+    /// Replaces the existing record of `yyyymmdd`
+    UpdateLunar {
+        creator: String,
+        yyyymmdd: u64,
+        lunar: Lunar,
+    },
+    /// Removes the existing record of `yyyymmdd`
+    DeleteLunar { creator: String, yyyymmdd: u64 },
 }
 
 impl ZodiaticMsg {
@@ -24,6 +32,18 @@ impl ZodiaticMsg {
             lunar,
         }
     }
+
+    pub fn update_lunar(creator: String, yyyymmdd: u64, lunar: Lunar) -> Self {
+        ZodiaticMsg::UpdateLunar {
+            creator,
+            yyyymmdd,
+            lunar,
+        }
+    }
+
+    pub fn delete_lunar(creator: String, yyyymmdd: u64) -> Self {
+        ZodiaticMsg::DeleteLunar { creator, yyyymmdd }
+    }
 }
 
 impl From<ZodiaticMsg> for CosmosMsg<ZodiaticMsg> {
@@ -0,0 +1,689 @@
+//! Protobuf encoding of the messages and queries of the Zodiatic module, following
+//! `proto/zodiatic/zodiatic/{lunar,tx,query}.proto` of the chain. Chains that prefer
+//! `CosmosMsg::Stargate` and gRPC queries to custom messages take these in place of
+//! `ZodiaticMsg` and `ZodiaticQuery`. Only built with the `stargate` feature.
+
+use cosmwasm_std::{Binary, CosmosMsg, QueryRequest, StdError, StdResult};
+use prost::Message;
+
+use crate::msg::ZodiaticMsg;
+use crate::query::{
+    self, LunarBatchResponse, LunarExistsResponse, LunarRangeResponse, LunarResponse,
+    ParamsResponse, ZodiaticQuery,
+};
+use crate::types;
+
+pub const MSG_CREATE_LUNAR: &str = "/zodiatic.zodiatic.MsgCreateLunar";
+pub const MSG_UPDATE_LUNAR: &str = "/zodiatic.zodiatic.MsgUpdateLunar";
+pub const MSG_DELETE_LUNAR: &str = "/zodiatic.zodiatic.MsgDeleteLunar";
+
+pub const QUERY_LUNAR: &str = "/zodiatic.zodiatic.Query/Lunar";
+pub const QUERY_LUNAR_RANGE: &str = "/zodiatic.zodiatic.Query/LunarRange";
+pub const QUERY_LUNAR_BATCH: &str = "/zodiatic.zodiatic.Query/LunarBatch";
+pub const QUERY_LUNAR_EXISTS: &str = "/zodiatic.zodiatic.Query/LunarExists";
+pub const QUERY_PARAMS: &str = "/zodiatic.zodiatic.Query/Params";
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Lunar {
+    #[prost(string, tag = "1")]
+    pub date: String,
+    #[prost(uint64, tag = "2")]
+    pub lunar_year: u64,
+    #[prost(uint64, tag = "3")]
+    pub lunar_month: u64,
+    #[prost(uint64, tag = "4")]
+    pub lunar_day: u64,
+    #[prost(string, tag = "5")]
+    pub lunar: String,
+    #[prost(string, tag = "6")]
+    pub eight_words: String,
+    #[prost(string, tag = "7")]
+    pub god_direction: String,
+    #[prost(string, tag = "8")]
+    pub good_for: String,
+    #[prost(string, tag = "9")]
+    pub bad_for: String,
+    #[prost(message, repeated, tag = "10")]
+    pub hours: Vec<LunarHour>,
+    #[prost(string, tag = "11")]
+    pub clash: String,
+    #[prost(string, tag = "12")]
+    pub sha_direction: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct LunarHour {
+    #[prost(string, tag = "1")]
+    pub hour: String,
+    #[prost(string, tag = "2")]
+    pub clash: String,
+    #[prost(string, tag = "3")]
+    pub good_for: String,
+    #[prost(string, tag = "4")]
+    pub bad_for: String,
+}
+
+/// A day and its record, if it has one
+#[derive(Clone, PartialEq, Message)]
+pub struct LunarEntry {
+    #[prost(uint64, tag = "1")]
+    pub yyyymmdd: u64,
+    #[prost(message, optional, tag = "2")]
+    pub lunar: Option<Lunar>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct Params {
+    #[prost(uint32, tag = "1")]
+    pub first_year: u32,
+    #[prost(uint32, tag = "2")]
+    pub last_year: u32,
+    #[prost(string, tag = "3")]
+    pub data_source: String,
+    #[prost(bool, tag = "4")]
+    pub permissioned_writes: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgCreateLunar {
+    #[prost(string, tag = "1")]
+    pub creator: String,
+    #[prost(uint64, tag = "2")]
+    pub yyyymmdd: u64,
+    #[prost(message, optional, tag = "3")]
+    pub lunar: Option<Lunar>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgUpdateLunar {
+    #[prost(string, tag = "1")]
+    pub creator: String,
+    #[prost(uint64, tag = "2")]
+    pub yyyymmdd: u64,
+    #[prost(message, optional, tag = "3")]
+    pub lunar: Option<Lunar>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgDeleteLunar {
+    #[prost(string, tag = "1")]
+    pub creator: String,
+    #[prost(uint64, tag = "2")]
+    pub yyyymmdd: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryLunarRequest {
+    #[prost(uint64, tag = "1")]
+    pub yyyymmdd: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryLunarResponse {
+    #[prost(message, optional, tag = "1")]
+    pub lunar: Option<Lunar>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryLunarRangeRequest {
+    #[prost(uint64, tag = "1")]
+    pub start: u64,
+    #[prost(uint64, tag = "2")]
+    pub end: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryLunarRangeResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub lunars: Vec<LunarEntry>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryLunarBatchRequest {
+    #[prost(uint64, repeated, tag = "1")]
+    pub keys: Vec<u64>,
+}
+
+/// One entry per key of the request, in the same order
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryLunarBatchResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub lunars: Vec<LunarEntry>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryLunarExistsRequest {
+    #[prost(uint64, tag = "1")]
+    pub yyyymmdd: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryLunarExistsResponse {
+    #[prost(bool, tag = "1")]
+    pub exists: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryParamsRequest {}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct QueryParamsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub params: Option<Params>,
+}
+
+/// Decodes `bytes` as the protobuf message `P` and converts it to `T`, e.g. a
+/// `QueryLunarResponse` to a `LunarResponse`
+pub fn decode<P, T>(bytes: &[u8]) -> StdResult<T>
+where
+    P: Message + Default,
+    T: TryFrom<P, Error = StdError>,
+{
+    T::try_from(decode_message::<P>(bytes)?)
+}
+
+/// Converts `value` to the protobuf message `P` and encodes it
+pub fn encode<P, T>(value: T) -> StdResult<Binary>
+where
+    P: Message + TryFrom<T>,
+    P::Error: ToString,
+{
+    let message = P::try_from(value).map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(Binary(message.encode_to_vec()))
+}
+
+fn type_name<P>() -> &'static str {
+    let name = std::any::type_name::<P>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+fn required<T>(field: Option<T>, name: &str) -> StdResult<T> {
+    field.ok_or_else(|| StdError::parse_err(name, "missing field"))
+}
+
+impl From<types::Lunar> for Lunar {
+    fn from(lunar: types::Lunar) -> Self {
+        let (lunar_year, lunar_month, lunar_day) = lunar.lunar_number;
+        Lunar {
+            date: lunar.date,
+            lunar_year,
+            lunar_month,
+            lunar_day,
+            lunar: lunar.lunar,
+            eight_words: lunar.eight_words,
+            god_direction: lunar.god_direction,
+            good_for: lunar.good_for,
+            bad_for: lunar.bad_for,
+            hours: lunar.hours.into_iter().map(LunarHour::from).collect(),
+            clash: lunar.clash,
+            sha_direction: lunar.sha_direction,
+        }
+    }
+}
+
+impl From<Lunar> for types::Lunar {
+    fn from(lunar: Lunar) -> Self {
+        types::Lunar {
+            date: lunar.date,
+            lunar_number: (lunar.lunar_year, lunar.lunar_month, lunar.lunar_day),
+            lunar: lunar.lunar,
+            eight_words: lunar.eight_words,
+            god_direction: lunar.god_direction,
+            good_for: lunar.good_for,
+            bad_for: lunar.bad_for,
+            hours: lunar
+                .hours
+                .into_iter()
+                .map(types::LunarHour::from)
+                .collect(),
+            clash: lunar.clash,
+            sha_direction: lunar.sha_direction,
+        }
+    }
+}
+
+impl From<types::LunarHour> for LunarHour {
+    fn from(hour: types::LunarHour) -> Self {
+        LunarHour {
+            hour: hour.hour,
+            clash: hour.clash,
+            good_for: hour.good_for,
+            bad_for: hour.bad_for,
+        }
+    }
+}
+
+impl From<LunarHour> for types::LunarHour {
+    fn from(hour: LunarHour) -> Self {
+        types::LunarHour {
+            hour: hour.hour,
+            clash: hour.clash,
+            good_for: hour.good_for,
+            bad_for: hour.bad_for,
+        }
+    }
+}
+
+impl From<query::Params> for Params {
+    fn from(params: query::Params) -> Self {
+        Params {
+            first_year: params.first_year,
+            last_year: params.last_year,
+            data_source: params.data_source,
+            permissioned_writes: params.permissioned_writes,
+        }
+    }
+}
+
+impl From<Params> for query::Params {
+    fn from(params: Params) -> Self {
+        query::Params {
+            first_year: params.first_year,
+            last_year: params.last_year,
+            data_source: params.data_source,
+            permissioned_writes: params.permissioned_writes,
+        }
+    }
+}
+
+impl From<LunarResponse> for QueryLunarResponse {
+    fn from(res: LunarResponse) -> Self {
+        QueryLunarResponse {
+            lunar: Some(res.lunar.into()),
+        }
+    }
+}
+
+impl TryFrom<QueryLunarResponse> for LunarResponse {
+    type Error = StdError;
+
+    fn try_from(res: QueryLunarResponse) -> StdResult<Self> {
+        Ok(LunarResponse {
+            lunar: required(res.lunar, "lunar")?.into(),
+        })
+    }
+}
+
+impl From<LunarRangeResponse> for QueryLunarRangeResponse {
+    fn from(res: LunarRangeResponse) -> Self {
+        let lunars = res
+            .lunars
+            .into_iter()
+            .map(|(yyyymmdd, lunar)| LunarEntry {
+                yyyymmdd,
+                lunar: Some(lunar.into()),
+            })
+            .collect();
+        QueryLunarRangeResponse { lunars }
+    }
+}
+
+impl TryFrom<QueryLunarRangeResponse> for LunarRangeResponse {
+    type Error = StdError;
+
+    fn try_from(res: QueryLunarRangeResponse) -> StdResult<Self> {
+        let lunars = res
+            .lunars
+            .into_iter()
+            .map(|entry| Ok((entry.yyyymmdd, required(entry.lunar, "lunar")?.into())))
+            .collect::<StdResult<_>>()?;
+        Ok(LunarRangeResponse { lunars })
+    }
+}
+
+/// The response does not know the keys of the request, so they are given along with it, one
+/// per record
+impl TryFrom<(Vec<u64>, LunarBatchResponse)> for QueryLunarBatchResponse {
+    type Error = StdError;
+
+    fn try_from((keys, res): (Vec<u64>, LunarBatchResponse)) -> StdResult<Self> {
+        if keys.len() != res.lunars.len() {
+            return Err(StdError::generic_err(format!(
+                "Expected {} records in the batch, got {}",
+                keys.len(),
+                res.lunars.len()
+            )));
+        }
+        let lunars = keys
+            .into_iter()
+            .zip(res.lunars)
+            .map(|(yyyymmdd, lunar)| LunarEntry {
+                yyyymmdd,
+                lunar: lunar.map(Lunar::from),
+            })
+            .collect();
+        Ok(QueryLunarBatchResponse { lunars })
+    }
+}
+
+impl TryFrom<QueryLunarBatchResponse> for LunarBatchResponse {
+    type Error = StdError;
+
+    fn try_from(res: QueryLunarBatchResponse) -> StdResult<Self> {
+        let lunars = res
+            .lunars
+            .into_iter()
+            .map(|entry| entry.lunar.map(types::Lunar::from))
+            .collect();
+        Ok(LunarBatchResponse { lunars })
+    }
+}
+
+impl From<LunarExistsResponse> for QueryLunarExistsResponse {
+    fn from(res: LunarExistsResponse) -> Self {
+        QueryLunarExistsResponse { exists: res.exists }
+    }
+}
+
+impl TryFrom<QueryLunarExistsResponse> for LunarExistsResponse {
+    type Error = StdError;
+
+    fn try_from(res: QueryLunarExistsResponse) -> StdResult<Self> {
+        Ok(LunarExistsResponse { exists: res.exists })
+    }
+}
+
+impl From<ParamsResponse> for QueryParamsResponse {
+    fn from(res: ParamsResponse) -> Self {
+        QueryParamsResponse {
+            params: Some(res.params.into()),
+        }
+    }
+}
+
+impl TryFrom<QueryParamsResponse> for ParamsResponse {
+    type Error = StdError;
+
+    fn try_from(res: QueryParamsResponse) -> StdResult<Self> {
+        Ok(ParamsResponse {
+            params: required(res.params, "params")?.into(),
+        })
+    }
+}
+
+impl ZodiaticMsg {
+    /// The type URL of the protobuf message that the module takes in place of this one
+    pub fn type_url(&self) -> &'static str {
+        match self {
+            ZodiaticMsg::CreateLunar { .. } => MSG_CREATE_LUNAR,
+            ZodiaticMsg::UpdateLunar { .. } => MSG_UPDATE_LUNAR,
+            ZodiaticMsg::DeleteLunar { .. } => MSG_DELETE_LUNAR,
+        }
+    }
+
+    /// Encodes the message as the protobuf message of `type_url`
+    pub fn to_proto(&self) -> Binary {
+        let bytes = match self.clone() {
+            ZodiaticMsg::CreateLunar {
+                creator,
+                yyyymmdd,
+                lunar,
+            } => MsgCreateLunar {
+                creator,
+                yyyymmdd,
+                lunar: Some(lunar.into()),
+            }
+            .encode_to_vec(),
+            ZodiaticMsg::UpdateLunar {
+                creator,
+                yyyymmdd,
+                lunar,
+            } => MsgUpdateLunar {
+                creator,
+                yyyymmdd,
+                lunar: Some(lunar.into()),
+            }
+            .encode_to_vec(),
+            ZodiaticMsg::DeleteLunar { creator, yyyymmdd } => {
+                MsgDeleteLunar { creator, yyyymmdd }.encode_to_vec()
+            }
+        };
+        Binary(bytes)
+    }
+
+    /// Decodes a protobuf message of the module, given its type URL
+    pub fn from_proto(type_url: &str, value: &[u8]) -> StdResult<Self> {
+        match type_url {
+            MSG_CREATE_LUNAR => {
+                let msg: MsgCreateLunar = decode_message(value)?;
+                let lunar = required(msg.lunar, "lunar")?.into();
+                Ok(ZodiaticMsg::create_lunar(msg.creator, msg.yyyymmdd, lunar))
+            }
+            MSG_UPDATE_LUNAR => {
+                let msg: MsgUpdateLunar = decode_message(value)?;
+                let lunar = required(msg.lunar, "lunar")?.into();
+                Ok(ZodiaticMsg::update_lunar(msg.creator, msg.yyyymmdd, lunar))
+            }
+            MSG_DELETE_LUNAR => {
+                let msg: MsgDeleteLunar = decode_message(value)?;
+                Ok(ZodiaticMsg::delete_lunar(msg.creator, msg.yyyymmdd))
+            }
+            _ => Err(StdError::parse_err(type_url, "unknown type URL")),
+        }
+    }
+
+    /// Wraps the message in a `CosmosMsg::Stargate`
+    pub fn into_stargate<T>(self) -> CosmosMsg<T> {
+        CosmosMsg::Stargate {
+            type_url: self.type_url().to_string(),
+            value: self.to_proto(),
+        }
+    }
+}
+
+impl ZodiaticQuery {
+    /// The gRPC method of the module that answers this query
+    pub fn grpc_path(&self) -> &'static str {
+        match self {
+            ZodiaticQuery::Lunar { .. } => QUERY_LUNAR,
+            ZodiaticQuery::LunarRange { .. } => QUERY_LUNAR_RANGE,
+            ZodiaticQuery::LunarBatch { .. } => QUERY_LUNAR_BATCH,
+            ZodiaticQuery::LunarExists { .. } => QUERY_LUNAR_EXISTS,
+            ZodiaticQuery::Params {} => QUERY_PARAMS,
+        }
+    }
+
+    /// Encodes the query as the request of `grpc_path`
+    pub fn to_proto(&self) -> Binary {
+        let bytes = match self.clone() {
+            ZodiaticQuery::Lunar { yyyymmdd } => QueryLunarRequest { yyyymmdd }.encode_to_vec(),
+            ZodiaticQuery::LunarRange { start, end } => {
+                QueryLunarRangeRequest { start, end }.encode_to_vec()
+            }
+            ZodiaticQuery::LunarBatch { keys } => QueryLunarBatchRequest { keys }.encode_to_vec(),
+            ZodiaticQuery::LunarExists { yyyymmdd } => {
+                QueryLunarExistsRequest { yyyymmdd }.encode_to_vec()
+            }
+            ZodiaticQuery::Params {} => QueryParamsRequest {}.encode_to_vec(),
+        };
+        Binary(bytes)
+    }
+
+    /// Decodes a request to a gRPC method of the module, given its path
+    pub fn from_proto(path: &str, data: &[u8]) -> StdResult<Self> {
+        match path {
+            QUERY_LUNAR => {
+                let req: QueryLunarRequest = decode_message(data)?;
+                Ok(ZodiaticQuery::lunar(req.yyyymmdd))
+            }
+            QUERY_LUNAR_RANGE => {
+                let req: QueryLunarRangeRequest = decode_message(data)?;
+                Ok(ZodiaticQuery::lunar_range(req.start, req.end))
+            }
+            QUERY_LUNAR_BATCH => {
+                let req: QueryLunarBatchRequest = decode_message(data)?;
+                Ok(ZodiaticQuery::lunar_batch(req.keys))
+            }
+            QUERY_LUNAR_EXISTS => {
+                let req: QueryLunarExistsRequest = decode_message(data)?;
+                Ok(ZodiaticQuery::lunar_exists(req.yyyymmdd))
+            }
+            QUERY_PARAMS => {
+                decode_message::<QueryParamsRequest>(data)?;
+                Ok(ZodiaticQuery::params())
+            }
+            _ => Err(StdError::parse_err(path, "unknown gRPC path")),
+        }
+    }
+
+    /// Wraps the query in a `QueryRequest::Stargate`. The response is the protobuf encoding
+    /// of the matching `Query*Response`, which `decode` turns into the usual response type.
+    pub fn into_stargate<T>(self) -> QueryRequest<T> {
+        QueryRequest::Stargate {
+            path: self.grpc_path().to_string(),
+            data: self.to_proto(),
+        }
+    }
+}
+
+fn decode_message<P: Message + Default>(bytes: &[u8]) -> StdResult<P> {
+    P::decode(bytes).map_err(|err| StdError::parse_err(type_name::<P>(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lunar() -> types::Lunar {
+        types::Lunar::new("2022-01-01", (2021, 11, 29), "冬月", "", "", "開市", "")
+            .with_clash("猴", "北")
+    }
+
+    #[test]
+    fn encodes_messages() {
+        let msg = ZodiaticMsg::delete_lunar("creator".into(), 20220101);
+        assert_eq!(MSG_DELETE_LUNAR, msg.type_url());
+        // creator = 1 (length-delimited), yyyymmdd = 2 (varint)
+        let bytes = hex("0a0763726561746f7210c591d209");
+        assert_eq!(bytes, msg.to_proto().to_vec());
+        assert_eq!(
+            msg,
+            ZodiaticMsg::from_proto(MSG_DELETE_LUNAR, &bytes).unwrap()
+        );
+
+        let msg = ZodiaticMsg::create_lunar("c".into(), 20220101, lunar());
+        let bytes = msg.to_proto();
+        let expected = [
+            "0a0163",                   // creator
+            "10c591d209",               // yyyymmdd
+            "1a2d",                     // lunar, 45 bytes
+            "0a0a323032322d30312d3031", // date
+            "10e50f",                   // lunar_year
+            "180b",                     // lunar_month
+            "201d",                     // lunar_day
+            "2a06e586ace69c88",         // lunar
+            "4206e9968be5b882",         // good_for
+            "5a03e78cb4",               // clash
+            "6203e58c97",               // sha_direction
+        ];
+        assert_eq!(hex(&expected.concat()), bytes.to_vec());
+        assert_eq!(
+            msg,
+            ZodiaticMsg::from_proto(MSG_CREATE_LUNAR, &bytes).unwrap()
+        );
+
+        let msg = ZodiaticMsg::update_lunar("c".into(), 20220101, lunar());
+        let bytes = msg.to_proto();
+        assert_eq!(
+            msg,
+            ZodiaticMsg::from_proto(MSG_UPDATE_LUNAR, &bytes).unwrap()
+        );
+
+        // a create without its record is malformed
+        ZodiaticMsg::from_proto(MSG_CREATE_LUNAR, &hex("0a0163")).unwrap_err();
+        ZodiaticMsg::from_proto("/zodiatic.zodiatic.MsgUnknown", &[]).unwrap_err();
+    }
+
+    #[test]
+    fn encodes_queries_and_responses() {
+        let cases = [
+            (ZodiaticQuery::lunar(20220101), QUERY_LUNAR, "08c591d209"),
+            (
+                ZodiaticQuery::lunar_range(20220101, 20220131),
+                QUERY_LUNAR_RANGE,
+                "08c591d20910e391d209",
+            ),
+            // keys are packed
+            (
+                ZodiaticQuery::lunar_batch(vec![20220101, 20220131]),
+                QUERY_LUNAR_BATCH,
+                "0a08c591d209e391d209",
+            ),
+            (
+                ZodiaticQuery::lunar_exists(20220101),
+                QUERY_LUNAR_EXISTS,
+                "08c591d209",
+            ),
+            (ZodiaticQuery::params(), QUERY_PARAMS, ""),
+        ];
+        for (query, path, bytes) in cases {
+            assert_eq!(path, query.grpc_path());
+            assert_eq!(hex(bytes), query.to_proto().to_vec());
+            assert_eq!(query, ZodiaticQuery::from_proto(path, &hex(bytes)).unwrap());
+        }
+
+        let res = LunarBatchResponse {
+            lunars: vec![None, Some(lunar())],
+        };
+        let keys = vec![20211231, 20220101];
+        encode::<QueryLunarBatchResponse, _>((vec![20220101], res.clone())).unwrap_err();
+        let bytes = encode::<QueryLunarBatchResponse, _>((keys.clone(), res.clone())).unwrap();
+        let entries = decode_message::<QueryLunarBatchResponse>(&bytes)
+            .unwrap()
+            .lunars;
+        assert_eq!(
+            keys,
+            entries
+                .iter()
+                .map(|entry| entry.yyyymmdd)
+                .collect::<Vec<_>>()
+        );
+        let decoded: LunarBatchResponse = decode::<QueryLunarBatchResponse, _>(&bytes).unwrap();
+        assert_eq!(res, decoded);
+
+        let res = ParamsResponse {
+            params: query::Params {
+                first_year: 1901,
+                last_year: 2100,
+                data_source: "hko".into(),
+                permissioned_writes: true,
+            },
+        };
+        let bytes = encode::<QueryParamsResponse, _>(res.clone()).unwrap();
+        assert_eq!(hex("0a0d08ed0e10b4101a03686b6f2001"), bytes.to_vec());
+        assert_eq!(res, decode::<QueryParamsResponse, _>(&bytes).unwrap());
+
+        // a response without its record is malformed
+        decode::<QueryLunarResponse, LunarResponse>(&[]).unwrap_err();
+        let res: LunarExistsResponse = decode::<QueryLunarExistsResponse, _>(&[]).unwrap();
+        assert!(!res.exists);
+    }
+
+    #[test]
+    fn wraps_in_stargate() {
+        let msg = ZodiaticMsg::delete_lunar("creator".into(), 20220101);
+        assert_eq!(
+            CosmosMsg::<cosmwasm_std::Empty>::Stargate {
+                type_url: MSG_DELETE_LUNAR.into(),
+                value: msg.to_proto(),
+            },
+            msg.into_stargate()
+        );
+
+        let query = ZodiaticQuery::lunar(20220101);
+        assert_eq!(
+            QueryRequest::<cosmwasm_std::Empty>::Stargate {
+                path: QUERY_LUNAR.into(),
+                data: Binary(hex("08c591d209")),
+            },
+            query.into_stargate()
+        );
+    }
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+}
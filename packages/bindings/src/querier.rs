@@ -1,6 +1,9 @@
 use cosmwasm_std::{QuerierWrapper, QueryRequest, StdResult};
 
-use crate::query::{LunarResponse, ZodiaticQuery};
+use crate::query::{
+    LunarBatchResponse, LunarExistsResponse, LunarRangeResponse, LunarResponse, ParamsResponse,
+    ZodiaticQuery,
+};
 
 /// This is a helper wrapper to easily use our custom queries
 pub struct ZodiaticQuerier<'a> {
@@ -17,4 +20,24 @@ impl<'a> ZodiaticQuerier<'a> {
         let request: QueryRequest<ZodiaticQuery> = ZodiaticQuery::into(lunar_query);
         self.querier.query(&request)
     }
+
+    pub fn lunar_range(&self, start: u64, end: u64) -> StdResult<LunarRangeResponse> {
+        let request: QueryRequest<ZodiaticQuery> = ZodiaticQuery::lunar_range(start, end).into();
+        self.querier.query(&request)
+    }
+
+    pub fn lunar_batch(&self, keys: Vec<u64>) -> StdResult<LunarBatchResponse> {
+        let request: QueryRequest<ZodiaticQuery> = ZodiaticQuery::lunar_batch(keys).into();
+        self.querier.query(&request)
+    }
+
+    pub fn lunar_exists(&self, yyyymmdd: u64) -> StdResult<LunarExistsResponse> {
+        let request: QueryRequest<ZodiaticQuery> = ZodiaticQuery::lunar_exists(yyyymmdd).into();
+        self.querier.query(&request)
+    }
+
+    pub fn params(&self) -> StdResult<ParamsResponse> {
+        let request: QueryRequest<ZodiaticQuery> = ZodiaticQuery::params().into();
+        self.querier.query(&request)
+    }
 }
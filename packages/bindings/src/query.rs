@@ -8,7 +8,23 @@ use crate::types::Lunar;
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ZodiaticQuery {
-    Lunar { yyyymmdd: u64 },
+    Lunar {
+        yyyymmdd: u64,
+    },
+    /// Every record from `start` to `end` (inclusive), earliest first
+    LunarRange {
+        start: u64,
+        end: u64,
+    },
+    /// The record of each of `keys`, in the same order, or `None` where there is none
+    LunarBatch {
+        keys: Vec<u64>,
+    },
+    LunarExists {
+        yyyymmdd: u64,
+    },
+    /// The settings of the native module
+    Params {},
 }
 
 impl CustomQuery for ZodiaticQuery {}
@@ -17,9 +33,59 @@ impl ZodiaticQuery {
     pub fn lunar(yyyymmdd: u64) -> Self {
         ZodiaticQuery::Lunar { yyyymmdd }
     }
+
+    pub fn lunar_range(start: u64, end: u64) -> Self {
+        ZodiaticQuery::LunarRange { start, end }
+    }
+
+    pub fn lunar_batch(keys: Vec<u64>) -> Self {
+        ZodiaticQuery::LunarBatch { keys }
+    }
+
+    pub fn lunar_exists(yyyymmdd: u64) -> Self {
+        ZodiaticQuery::LunarExists { yyyymmdd }
+    }
+
+    pub fn params() -> Self {
+        ZodiaticQuery::Params {}
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct LunarResponse {
     pub lunar: Lunar,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LunarRangeResponse {
+    /// `(yyyymmdd, record)`
+    pub lunars: Vec<(u64, Lunar)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LunarBatchResponse {
+    pub lunars: Vec<Option<Lunar>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LunarExistsResponse {
+    pub exists: bool,
+}
+
+/// Settings of the native Zodiatic module
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Params {
+    /// First year that the module has records for
+    pub first_year: u32,
+    /// Last year that the module has records for
+    pub last_year: u32,
+    /// Where the records come from, e.g. the almanac edition they were taken from
+    pub data_source: String,
+    /// Whether only accounts approved by governance may create, update or delete records
+    pub permissioned_writes: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ParamsResponse {
+    pub params: Params,
+}
@@ -0,0 +1,149 @@
+/// The 24 solar terms in calendar order, from 小寒 in early January to 冬至 in December
+pub const SOLAR_TERMS: [&str; 24] = [
+    "小寒", "大寒", "立春", "雨水", "驚蟄", "春分", "清明", "穀雨", "立夏", "小滿", "芒種", "夏至",
+    "小暑", "大暑", "立秋", "處暑", "白露", "秋分", "寒露", "霜降", "立冬", "小雪", "大雪", "冬至",
+];
+
+/// The years that `solar_terms` can compute
+pub const SOLAR_TERM_YEARS: std::ops::RangeInclusive<u32> = 1901..=2100;
+
+/// `D` of the formula below, scaled by 10000
+const D: u32 = 2422;
+
+/// `C` of the formula below for each term, scaled by 10000: (21st century, 20th century)
+const C: [(u32, u32); 24] = [
+    (54055, 61100),
+    (201200, 208400),
+    (38700, 46295),
+    (187300, 194599),
+    (56300, 63826),
+    (206460, 214155),
+    (48100, 55900),
+    (201000, 208880),
+    (55200, 63180),
+    (210400, 218600),
+    (56780, 65000),
+    (213700, 222000),
+    (71080, 79280),
+    (228300, 236500),
+    (75000, 83500),
+    (231300, 239500),
+    (76460, 84400),
+    (230420, 238220),
+    (83180, 90980),
+    (234380, 242180),
+    (74380, 82180),
+    (223600, 230800),
+    (71800, 79000),
+    (219400, 226000),
+];
+
+/// Years where the formula is a day off: (year, index in `SOLAR_TERMS`, correction)
+const EXCEPTIONS: &[(u32, usize, i32)] = &[
+    (1902, 10, 1),
+    (1911, 8, 1),
+    (1918, 23, -1),
+    (1922, 13, 1),
+    (1925, 12, 1),
+    (1927, 16, 1),
+    (1928, 11, 1),
+    (1942, 17, 1),
+    (1954, 22, 1),
+    (1978, 21, 1),
+    (1982, 0, 1),
+    (2002, 14, 1),
+    (2008, 9, 1),
+    (2016, 12, 1),
+    (2019, 0, -1),
+    (2021, 23, -1),
+    (2026, 3, -1),
+    (2082, 1, 1),
+    (2084, 5, 1),
+    (2089, 19, 1),
+    (2089, 20, 1),
+];
+
+/// Returns the date (yyyymmdd, in China Standard Time) of each solar term of `year`, in the
+/// order of `SOLAR_TERMS`, or `None` if `year` is outside `SOLAR_TERM_YEARS`.
+///
+/// The day of the month is `[Y * D + C] - L`, where `Y` is the year within its century,
+/// `D` is 0.2422, `C` depends on the term and the century, and `L` is the number of leap
+/// years so far in the century (`[(Y - 1) / 4]` for the terms of January and February).
+/// Everything is computed in integers so that contracts can use it.
+pub fn solar_terms(year: u32) -> Option<Vec<(&'static str, u64)>> {
+    if !SOLAR_TERM_YEARS.contains(&year) {
+        return None;
+    }
+    let (y, c21) = if year > 2000 {
+        (year - 2000, true)
+    } else {
+        (year - 1900, false)
+    };
+    let terms = SOLAR_TERMS
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let c = if c21 { C[i].0 } else { C[i].1 };
+            let l = if i < 4 { (y - 1) / 4 } else { y / 4 };
+            let correction = EXCEPTIONS
+                .iter()
+                .find(|(exception, term, _)| *exception == year && *term == i)
+                .map_or(0, |(_, _, correction)| *correction);
+            let day = ((y * D + c) / 10000 - l) as i32 + correction;
+            let month = i / 2 + 1;
+            (*name, year as u64 * 10000 + month as u64 * 100 + day as u64)
+        })
+        .collect();
+    Some(terms)
+}
+
+/// Returns the solar term that starts on `yyyymmdd`, if any
+pub fn solar_term_on(yyyymmdd: u64) -> Option<&'static str> {
+    let year = u32::try_from(yyyymmdd / 10000).ok()?;
+    solar_terms(year)?
+        .into_iter()
+        .find(|(_, day)| *day == yyyymmdd)
+        .map(|(name, _)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_solar_terms() {
+        let days = solar_terms(2022)
+            .unwrap()
+            .into_iter()
+            .map(|(_, yyyymmdd)| yyyymmdd % 10000)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                105, 120, 204, 219, 305, 320, 405, 420, 505, 521, 606, 621, 707, 723, 807, 823,
+                907, 923, 1008, 1023, 1107, 1122, 1207, 1222
+            ],
+            days
+        );
+
+        // 1901 falls in the 20th century, and 2000 does too
+        assert_eq!(("小寒", 19010106), solar_terms(1901).unwrap()[0]);
+        assert_eq!(("冬至", 20001221), solar_terms(2000).unwrap()[23]);
+        assert_eq!(None, solar_terms(1900));
+        assert_eq!(None, solar_terms(2101));
+    }
+
+    #[test]
+    fn applies_exceptions() {
+        assert_eq!(("小寒", 20190105), solar_terms(2019).unwrap()[0]);
+        assert_eq!(("小暑", 20160707), solar_terms(2016).unwrap()[12]);
+        assert_eq!(("冬至", 20211221), solar_terms(2021).unwrap()[23]);
+        assert_eq!(("雨水", 20260218), solar_terms(2026).unwrap()[3]);
+    }
+
+    #[test]
+    fn finds_solar_term_on_a_day() {
+        assert_eq!(Some("清明"), solar_term_on(20220405));
+        assert_eq!(None, solar_term_on(20220406));
+        assert_eq!(None, solar_term_on(18000105));
+    }
+}
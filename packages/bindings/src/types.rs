@@ -1,6 +1,13 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::convert::to_traditional;
+
+/// The 12 double-hours (時辰) of a day, starting with 子時 at 23:00
+pub const HOURS: [&str; 12] = [
+    "子時", "丑時", "寅時", "卯時", "辰時", "巳時", "午時", "未時", "申時", "酉時", "戌時", "亥時",
+];
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Lunar {
     pub date: String,
@@ -10,6 +17,49 @@ pub struct Lunar {
     pub god_direction: String,
     pub good_for: String,
     pub bad_for: String,
+    /// Almanac of each double-hour, if known
+    #[serde(default)]
+    pub hours: Vec<LunarHour>,
+    /// The zodiac animal that the day clashes with (沖), one of `ANIMALS`
+    #[serde(default)]
+    pub clash: String,
+    /// The direction of the day's 煞, e.g. 南
+    #[serde(default)]
+    pub sha_direction: String,
+}
+
+/// Almanac of one double-hour of a day
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LunarHour {
+    /// One of `HOURS`
+    pub hour: String,
+    /// The animal that the double-hour clashes with, e.g. 馬
+    pub clash: String,
+    pub good_for: String,
+    pub bad_for: String,
+}
+
+impl LunarHour {
+    pub fn new(hour: &str, clash: &str, good_for: &str, bad_for: &str) -> LunarHour {
+        LunarHour {
+            hour: hour.into(),
+            clash: clash.into(),
+            good_for: good_for.into(),
+            bad_for: bad_for.into(),
+        }
+    }
+}
+
+/// Returns the name in `HOURS` of a double-hour given by name or by its earthly branch alone,
+/// in either script (e.g. 子時, 子时 or 子). Some almanac data misspells the branch 丑 as 醜,
+/// which is taken as 丑.
+pub fn hour_name(hour: &str) -> Option<&'static str> {
+    let hour = to_traditional(hour.trim()).replace('醜', "丑");
+    let branch = hour.strip_suffix('時').unwrap_or(&hour);
+    HOURS
+        .iter()
+        .find(|name| name.strip_suffix('時') == Some(branch))
+        .copied()
 }
 
 impl Lunar {
@@ -30,6 +80,21 @@ impl Lunar {
             god_direction: god_direction.into(),
             good_for: good_for.into(),
             bad_for: bad_for.into(),
+            hours: vec![],
+            clash: "".into(),
+            sha_direction: "".into(),
+        }
+    }
+
+    pub fn with_hours(self, hours: Vec<LunarHour>) -> Lunar {
+        Lunar { hours, ..self }
+    }
+
+    pub fn with_clash(self, clash: &str, sha_direction: &str) -> Lunar {
+        Lunar {
+            clash: clash.into(),
+            sha_direction: sha_direction.into(),
+            ..self
         }
     }
 }
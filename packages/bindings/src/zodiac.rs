@@ -0,0 +1,51 @@
+use crate::convert::to_traditional;
+
+/// The 12 zodiac animals, in the order of the earthly branches 子 to 亥
+pub const ANIMALS: [&str; 12] = [
+    "鼠", "牛", "虎", "兔", "龍", "蛇", "馬", "羊", "猴", "雞", "狗", "豬",
+];
+
+/// Returns the zodiac animal of a lunar year. 4 CE was a year of the rat (甲子).
+///
+/// A solar year only approximates the lunar year: someone born in January or February
+/// before the lunar new year belongs to the animal of the year before.
+pub fn zodiac_animal(year: i32) -> &'static str {
+    // 4 ≡ 4 (mod 12), so adding 8 instead of subtracting 4 cannot overflow
+    ANIMALS[((year.rem_euclid(12) + 8) % 12) as usize]
+}
+
+/// Returns the animal in `ANIMALS` matching `animal` in either script, if any. The clash
+/// form of almanacs, e.g. "沖虎", gives the animal clashed with.
+pub fn animal_name(animal: &str) -> Option<&'static str> {
+    let animal = to_traditional(animal.trim());
+    let animal = animal
+        .strip_prefix('沖')
+        .or_else(|| animal.strip_prefix('冲'))
+        .unwrap_or(&animal);
+    ANIMALS.iter().find(|name| **name == animal).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_animal_of_any_year() {
+        assert_eq!("鼠", zodiac_animal(4));
+        assert_eq!("虎", zodiac_animal(2022));
+        assert_eq!("豬", zodiac_animal(3));
+        assert_eq!("鼠", zodiac_animal(-8));
+        // no overflow at the extremes
+        assert_eq!("鼠", zodiac_animal(i32::MIN));
+        assert_eq!("兔", zodiac_animal(i32::MAX));
+    }
+
+    #[test]
+    fn reads_animal_names() {
+        assert_eq!(Some("龍"), animal_name(" 龙"));
+        assert_eq!(Some("虎"), animal_name("沖虎"));
+        assert_eq!(Some("龍"), animal_name("冲龙"));
+        assert_eq!(None, animal_name("沖"));
+        assert_eq!(None, animal_name("午"));
+    }
+}